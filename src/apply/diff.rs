@@ -0,0 +1,164 @@
+//! Compare what an apply would write with the config files on disk, used by
+//! `axtc diff`.
+
+use anyhow::Result;
+use similar::TextDiff;
+use std::path::{Path, PathBuf};
+
+use super::{AppSpec, WriteableTheme, render_all};
+use crate::constants::CONFIG_DIR;
+use crate::template::search::TemplateSearch;
+use crate::theme::Theme;
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const CYAN: &str = "\x1b[36m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// One rendered config file next to the file currently at its destination.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileDiff {
+    /// Name of the app the file belongs to.
+    pub app: String,
+    /// Where the file would be written.
+    pub dest: PathBuf,
+    /// Contents on disk, or `None` if the file does not exist yet.
+    pub current: Option<String>,
+    /// Contents the apply would write.
+    pub rendered: String,
+}
+
+impl FileDiff {
+    /// Whether applying would change the file.
+    pub fn is_changed(&self) -> bool {
+        self.current.as_deref() != Some(self.rendered.as_str())
+    }
+
+    /// The change as a unified diff with three lines of context, colored with
+    /// ANSI escapes when `color` is set. Empty when nothing would change.
+    pub fn unified(&self, color: bool) -> String {
+        if !self.is_changed() {
+            return String::new();
+        }
+        let dest = self.dest.display().to_string();
+        let old_header = match self.current {
+            Some(_) => dest.as_str(),
+            None => "/dev/null",
+        };
+        let text = TextDiff::from_lines(self.current.as_deref().unwrap_or(""), &self.rendered);
+        let diff = text
+            .unified_diff()
+            .context_radius(3)
+            .header(old_header, &dest)
+            .to_string();
+        match color {
+            true => diff.lines().map(colorize).collect(),
+            false => diff,
+        }
+    }
+}
+
+fn colorize(line: &str) -> String {
+    let style = if line.starts_with("---") || line.starts_with("+++") {
+        BOLD
+    } else if line.starts_with('+') {
+        GREEN
+    } else if line.starts_with('-') {
+        RED
+    } else if line.starts_with("@@") {
+        CYAN
+    } else {
+        return format!("{line}\n");
+    };
+    format!("{style}{line}{RESET}\n")
+}
+
+/// Render every app in `apps` with templates from `templates` and compare each
+/// file with its destination under the config directory. Nothing is written.
+pub fn diff(theme: &Theme, apps: &[AppSpec], templates: &TemplateSearch) -> Result<Vec<FileDiff>> {
+    Ok(compare(&render_all(theme, apps, templates)?, &CONFIG_DIR))
+}
+
+fn compare(rendered: &[(&AppSpec, WriteableTheme)], root: &Path) -> Vec<FileDiff> {
+    rendered
+        .iter()
+        .map(|(app, cfg)| {
+            let dest = root.join(&cfg.rel_path);
+            FileDiff {
+                app: app.name.clone(),
+                current: std::fs::read(&dest)
+                    .ok()
+                    .map(|bytes| String::from_utf8_lossy(&bytes).into_owned()),
+                dest,
+                rendered: cfg.content.clone(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apply::{App, ConfigFile};
+
+    fn rendered(rel: &str, content: &str) -> WriteableTheme {
+        WriteableTheme {
+            content: content.into(),
+            rel_path: PathBuf::from(rel),
+            mode: ConfigFile::REGULAR,
+        }
+    }
+
+    #[test]
+    fn compares_against_files_on_disk() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("picom")).unwrap();
+        std::fs::write(tmp.path().join("picom/picom.conf"), "a\nb\nc\n").unwrap();
+        std::fs::create_dir_all(tmp.path().join("alacritty")).unwrap();
+        std::fs::write(tmp.path().join("alacritty/alacritty.toml"), "same\n").unwrap();
+
+        let (picom, alacritty, polybar) = (
+            App::Picom.into(),
+            App::Alacritty.into(),
+            App::Polybar.into(),
+        );
+        let diffs = compare(
+            &[
+                (&picom, rendered("picom/picom.conf", "a\nB\nc\n")),
+                (&alacritty, rendered("alacritty/alacritty.toml", "same\n")),
+                (&polybar, rendered("polybar/config.ini", "new\n")),
+            ],
+            tmp.path(),
+        );
+
+        let changed: Vec<&str> = diffs
+            .iter()
+            .filter(|d| d.is_changed())
+            .map(|d| d.app.as_str())
+            .collect();
+        assert_eq!(changed, ["picom", "polybar"]);
+
+        let picom_diff = diffs[0].unified(false);
+        assert!(
+            picom_diff.contains("@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n"),
+            "{picom_diff}"
+        );
+        assert!(diffs[1].unified(false).is_empty());
+        assert!(diffs[2].unified(false).starts_with("--- /dev/null\n"));
+    }
+
+    #[test]
+    fn colored_diff_marks_lines() {
+        let diff = FileDiff {
+            app: "picom".into(),
+            dest: PathBuf::from("/c/picom.conf"),
+            current: Some("old\n".into()),
+            rendered: "new\n".into(),
+        };
+        let out = diff.unified(true);
+        assert!(out.contains(&format!("{RED}-old{RESET}\n")), "{out}");
+        assert!(out.contains(&format!("{GREEN}+new{RESET}\n")), "{out}");
+        assert!(out.starts_with(&format!("{BOLD}--- /c/picom.conf{RESET}\n")));
+    }
+}
@@ -1,20 +1,55 @@
 //! Applies a theme by rendering each app's template and writing the output to its config path.
+//!
+//! The apps to apply are described by [`AppSpec`]s: the four built-in [`App`]s
+//! provide defaults, and the axtc config file can override them or declare
+//! new ones (see [`crate::config`]).
 
-use anyhow::{Context, Result};
+pub mod diff;
+pub mod reload;
+pub mod select;
+
+use anyhow::{Context, Result, bail, ensure};
+use std::collections::HashSet;
+use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
-use strum::IntoEnumIterator;
 
-use crate::constants::{CONFIG_DIR, TEMPLATES_DIR};
+use crate::backup::{BackupStore, Snapshot};
+use crate::constants::CONFIG_DIR;
+use crate::template::search::TemplateSearch;
 use crate::theme::Theme;
+use reload::ReloadStep;
 
 /// A writeable theme that has been processed by Tera.
 pub struct WriteableTheme {
     pub(crate) content: String,
     pub(crate) rel_path: PathBuf,
+    pub(crate) mode: u32,
+}
+
+/// A config file written by an app, relative to the config directory.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigFile {
+    /// Template to render, relative to the templates directory unless absolute
+    /// (e.g. `polybar/launch.py.tera`).
+    pub template: PathBuf,
+    /// Path relative to the config directory (e.g. `polybar/launch.py`).
+    pub rel_path: PathBuf,
+    /// Unix permission bits used when the file is created fresh.
+    pub mode: u32,
+}
+
+impl ConfigFile {
+    pub(crate) const REGULAR: u32 = 0o644;
+    pub(crate) const SCRIPT: u32 = 0o755;
+
+    /// Whether the file must be executable (e.g. a script run by the app).
+    pub fn is_executable(&self) -> bool {
+        self.mode & 0o111 != 0
+    }
 }
 
-/// An application managed by axtc.
-#[derive(Clone, Copy, Debug, strum::Display, strum::EnumIter)]
+/// A built-in application managed by axtc.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::Display, strum::EnumIter)]
 #[strum(serialize_all = "lowercase")]
 pub enum App {
     /// herbstluftwm window manager.
@@ -28,38 +63,118 @@ pub enum App {
 }
 
 impl App {
-    /// Get the relative path to a series of config files based on the app type.
-    pub fn get_config_file_rel_paths(&self) -> Vec<PathBuf> {
+    /// Get the relative path and output metadata of a series of config files based on the app type.
+    ///
+    /// Each file is rendered from the template at the same relative path with a
+    /// `.tera` extension added.
+    pub fn get_config_file_rel_paths(&self) -> Vec<ConfigFile> {
         let prefix = self.to_string();
         match self {
-            Self::Herbstluftwm => vec!["autostart"],
-            Self::Alacritty => vec!["alacritty.toml"],
-            Self::Polybar => vec!["config.ini", "launch.py", "scripts/tags.py"],
-            Self::Picom => vec!["picom.conf"],
+            Self::Herbstluftwm => vec![("autostart", ConfigFile::SCRIPT)],
+            Self::Alacritty => vec![("alacritty.toml", ConfigFile::REGULAR)],
+            Self::Polybar => vec![
+                ("config.ini", ConfigFile::REGULAR),
+                ("launch.py", ConfigFile::SCRIPT),
+                ("scripts/tags.py", ConfigFile::SCRIPT),
+            ],
+            Self::Picom => vec![("picom.conf", ConfigFile::REGULAR)],
         }
         .into_iter()
-        .map(|p| Path::new(&prefix).join(PathBuf::from(p)))
+        .map(|(p, mode)| {
+            let rel_path = Path::new(&prefix).join(p);
+            ConfigFile {
+                template: rel_path.with_added_extension("tera"),
+                rel_path,
+                mode,
+            }
+        })
         .collect()
     }
 
-    /// Attempt to render all configs associated with an application. If a theme template is missing
-    /// it is silently ignored. However, if one of the templates exists and fails to render
-    /// correctly an error is returned instead.
-    pub fn render_theme(&self, theme: &Theme) -> Result<Vec<WriteableTheme>> {
-        let found_templates = self.get_config_file_rel_paths().into_iter().flat_map(|p| {
-            let t = TEMPLATES_DIR.join(&p).with_added_extension("tera");
-            if !t.exists() || !t.is_file() {
-                println!("[{}] template '{}' not found, skipping", *self, p.display());
+    /// Theme sections that enable the app. Each app is enabled by its own
+    /// section; alacritty is also enabled by `[ansi]`, since its config is
+    /// mostly the terminal palette.
+    pub fn sections(&self) -> Vec<String> {
+        match self {
+            Self::Alacritty => vec![self.to_string(), "ansi".to_owned()],
+            _ => vec![self.to_string()],
+        }
+    }
+}
+
+/// Everything axtc needs to theme one application: what to render, where to
+/// write it, and how to reload the running app afterwards.
+#[derive(Clone, Debug)]
+pub struct AppSpec {
+    /// Name used in output and to match overrides in the config file.
+    pub name: String,
+    /// Theme sections that enable this app (e.g. `polybar`). The app is
+    /// rendered when the theme has any of them, or always when empty.
+    pub sections: Vec<String>,
+    /// Files rendered for this app, in write order.
+    pub files: Vec<ConfigFile>,
+    /// Reload commands, in fallback order. Empty when the app needs no reload.
+    pub reload: Vec<ReloadStep>,
+}
+
+impl From<App> for AppSpec {
+    fn from(app: App) -> Self {
+        Self {
+            name: app.to_string(),
+            sections: app.sections(),
+            files: app.get_config_file_rel_paths(),
+            reload: app.reload_steps(),
+        }
+    }
+}
+
+impl AppSpec {
+    /// Why this app should be left out when applying the theme `table`, if it
+    /// should: none of its sections are present, or one of them sets
+    /// `enabled = false`.
+    fn skip_reason(&self, table: &toml::Table) -> Option<SkipReason> {
+        if !self.sections.is_empty() && !self.sections.iter().any(|s| table.contains_key(s)) {
+            return Some(SkipReason::MissingSection(self.sections.clone()));
+        }
+        self.sections
+            .iter()
+            .find(|s| {
+                table
+                    .get(s.as_str())
+                    .and_then(|section| section.get(ENABLED_KEY))
+                    .and_then(toml::Value::as_bool)
+                    == Some(false)
+            })
+            .map(|s| SkipReason::Disabled(s.clone()))
+    }
+
+    /// Attempt to render all configs associated with an application, taking each
+    /// template from the first layer of `templates` that has it. If a template is
+    /// missing from every layer it is skipped with a message. However, if one of
+    /// the templates exists and fails to render correctly an error is returned instead.
+    pub fn render_theme(
+        &self,
+        theme: &Theme,
+        templates: &TemplateSearch,
+    ) -> Result<Vec<WriteableTheme>> {
+        let found_templates = self.files.iter().flat_map(|f| {
+            let Some(t) = templates.find(&f.template) else {
+                println!(
+                    "[{}] template '{}' not found, skipping",
+                    self.name,
+                    f.template.display()
+                );
                 return None;
-            }
-            Some((p, t))
+            };
+            Some((f, t))
         });
 
         let mut files = vec![];
-        for (rel_path, tpl) in found_templates {
+        for (file, tpl) in found_templates {
             files.push(WriteableTheme {
-                content: crate::template::render(&tpl, theme)?,
-                rel_path,
+                content: crate::template::render_str(&tpl.read()?, theme)?,
+                rel_path: file.rel_path.clone(),
+                mode: file.mode,
             });
         }
 
@@ -67,50 +182,211 @@ impl App {
     }
 }
 
-/// Render and write config files for all apps present in `theme`.
+/// Key that turns an app off from inside its theme section (`[picom] enabled = false`).
+pub const ENABLED_KEY: &str = "enabled";
+
+/// Why an app was left out of an apply.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SkipReason {
+    /// The theme has none of the app's sections.
+    MissingSection(Vec<String>),
+    /// The named section sets `enabled = false`.
+    Disabled(String),
+}
+
+impl std::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingSection(sections) => {
+                let names: Vec<String> = sections.iter().map(|s| format!("[{s}]")).collect();
+                write!(f, "theme has no {} section", names.join(" or "))
+            }
+            Self::Disabled(section) => write!(f, "{section}.{ENABLED_KEY} is false"),
+        }
+    }
+}
+
+/// Apps left out of an apply, each with the reason.
+pub type Skipped<'a> = Vec<(&'a AppSpec, SkipReason)>;
+
+/// Split `apps` into those `theme` enables and those it skips, with the reason.
+pub fn enabled_apps<'a>(
+    theme: &Theme,
+    apps: &'a [AppSpec],
+) -> Result<(Vec<&'a AppSpec>, Skipped<'a>)> {
+    let table = toml::Table::try_from(theme).context("could not serialize theme")?;
+    let mut enabled = vec![];
+    let mut skipped = vec![];
+    for app in apps {
+        match app.skip_reason(&table) {
+            Some(reason) => skipped.push((app, reason)),
+            None => enabled.push(app),
+        }
+    }
+    Ok((enabled, skipped))
+}
+
+/// What a successful [`apply`] wrote.
+pub struct Applied<'a> {
+    /// Apps that had at least one file written, in write order.
+    pub apps: Vec<&'a AppSpec>,
+    /// Every file written, in write order.
+    pub files: Vec<PathBuf>,
+}
+
+impl std::fmt::Display for AppSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// Render and write config files for every app in `apps`, with templates
+/// looked up in `templates`.
+///
+/// Apps the theme does not enable (see [`enabled_apps`]) and apps whose
+/// templates are all missing are skipped, with a line saying why.
+/// Existing config files are backed up before being overwritten, and every
+/// written destination is recorded in a backup manifest so the apply can be
+/// undone with `axtc restore`.
 ///
-/// Apps whose section is absent from the theme are silently skipped.
-/// Existing config files are backed up before being overwritten.
+/// The apply is all-or-nothing: every template is rendered before anything is
+/// written, and if any write fails the files already written in this run are
+/// restored from the backups it took.
 ///
 /// When in "dry run" mode, rendered output is written to the same relative
 /// path under the current directory instead of the real config locations, and
 /// no backups are created.
-pub fn apply(theme: &Theme, dry_run: bool) -> Result<()> {
-    let writeable_cfgs = App::iter()
-        .map(|app| (app, app.render_theme(theme)))
-        .collect::<Vec<_>>();
-
-    for (app, cfg_list) in writeable_cfgs {
-        for cfg in cfg_list? {
-            backup_and_write(app, &cfg.rel_path, &cfg.content, dry_run)?;
+///
+/// Returns the apps and files that were written.
+pub fn apply<'a>(
+    theme: &Theme,
+    apps: &'a [AppSpec],
+    templates: &TemplateSearch,
+    dry_run: bool,
+) -> Result<Applied<'a>> {
+    let rendered = render_all(theme, apps, templates)?;
+
+    let (root, snapshot) = match dry_run {
+        true => (Path::new("."), None),
+        false => (
+            CONFIG_DIR.as_path(),
+            Some(BackupStore::default().begin(&theme.name)?),
+        ),
+    };
+
+    write_all(&rendered, root, snapshot)
+}
+
+/// Theme keys that no template of `apps` references, for `apply --strict`.
+///
+/// See [`crate::template::unreferenced_keys`] for what counts as a reference.
+pub fn unreferenced_keys(
+    theme: &Theme,
+    apps: &[AppSpec],
+    templates: &TemplateSearch,
+) -> Result<Vec<String>> {
+    let sources = apps
+        .iter()
+        .flat_map(|app| &app.files)
+        .filter_map(|f| templates.find(&f.template))
+        .map(|t| t.read())
+        .collect::<Result<Vec<_>>>()?;
+    crate::template::unreferenced_keys(theme, &sources)
+}
+
+/// Render every app the theme enables, failing with all render errors at once
+/// if any template is broken.
+fn render_all<'a>(
+    theme: &Theme,
+    apps: &'a [AppSpec],
+    templates: &TemplateSearch,
+) -> Result<Vec<(&'a AppSpec, WriteableTheme)>> {
+    let (enabled, skipped) = enabled_apps(theme, apps)?;
+    for (app, reason) in &skipped {
+        println!("[{app}] skipped: {reason}");
+    }
+
+    let mut rendered = vec![];
+    let mut errors = vec![];
+    for app in enabled {
+        match app.render_theme(theme, templates) {
+            Ok(cfgs) => rendered.extend(cfgs.into_iter().map(|cfg| (app, cfg))),
+            Err(e) => errors.push(format!("[{app}] {e:#}")),
         }
     }
+    if !errors.is_empty() {
+        bail!(
+            "nothing was written; rendering failed:\n{}",
+            errors.join("\n")
+        );
+    }
 
-    Ok(())
+    let mut seen = HashSet::new();
+    for (app, cfg) in &rendered {
+        ensure!(
+            seen.insert(&cfg.rel_path),
+            "[{app}] '{}' is rendered more than once",
+            cfg.rel_path.display()
+        );
+    }
+    Ok(rendered)
 }
 
-fn backup_and_write(app: App, rel: &Path, content: &str, dry_run: bool) -> Result<()> {
-    let dest = match dry_run {
-        true => Path::new(".").join(rel),
-        false => CONFIG_DIR.join(rel),
+/// Write every rendered file under `root`, rolling back on the first failure.
+fn write_all<'a>(
+    rendered: &[(&'a AppSpec, WriteableTheme)],
+    root: &Path,
+    mut snapshot: Option<Snapshot>,
+) -> Result<Applied<'a>> {
+    let mut written = Applied {
+        apps: vec![],
+        files: vec![],
     };
+    for &(app, ref cfg) in rendered {
+        let dest = root.join(&cfg.rel_path);
+        let result = backup_and_write(
+            app,
+            &dest,
+            &cfg.rel_path,
+            &cfg.content,
+            cfg.mode,
+            snapshot.as_mut(),
+        );
+        if let Err(e) = result {
+            if let Some(snapshot) = snapshot {
+                println!("[{app}] write failed, rolling back");
+                snapshot
+                    .rollback()
+                    .context("rollback after failed write also failed")?;
+            }
+            return Err(e);
+        }
+        if !written.apps.iter().any(|w| w.name == app.name) {
+            written.apps.push(app);
+        }
+        written.files.push(dest);
+    }
 
+    if let Some(snapshot) = snapshot
+        && !snapshot.manifest().files.is_empty()
+    {
+        println!("recorded backup {}", snapshot.manifest().id);
+    }
+    Ok(written)
+}
+
+fn backup_and_write(
+    app: &AppSpec,
+    dest: &Path,
+    rel: &Path,
+    content: &str,
+    mode: u32,
+    snapshot: Option<&mut Snapshot>,
+) -> Result<()> {
     // Create backup
-    if dest.exists() && !dry_run {
-        let backup_dir = CONFIG_DIR
-            .join("axtc")
-            .join("backups")
-            .join(app.to_string());
-        std::fs::create_dir_all(&backup_dir)?;
-
-        let filename = dest.file_name().unwrap().to_string_lossy();
-        let ts = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        let backup_path = backup_dir.join(format!("{ts}_{filename}"));
-        std::fs::copy(&dest, &backup_path)
-            .with_context(|| format!("could not backup '{}'", dest.display()))?;
+    if let Some(snapshot) = snapshot
+        && let Some(backup_path) = snapshot.record(dest, rel)?
+    {
         println!(
             "[{}] backed up {} → {}",
             app,
@@ -119,15 +395,215 @@ fn backup_and_write(app: App, rel: &Path, content: &str, dry_run: bool) -> Resul
         );
     }
 
-    // Write config
+    // Write config via a temp file so a failure never leaves a half-written file
     if let Some(parent) = dest.parent() {
         std::fs::create_dir_all(parent)?;
     }
 
-    std::fs::write(&dest, content)
-        .with_context(|| format!("could not write '{}'", dest.display()))?;
+    let name = dest.file_name().unwrap().to_string_lossy();
+    let tmp = dest.with_file_name(format!(".{name}.axtc-tmp"));
+    std::fs::write(&tmp, content)
+        .with_context(|| format!("could not write '{}'", tmp.display()))?;
+    std::fs::set_permissions(
+        &tmp,
+        std::fs::Permissions::from_mode(output_mode(dest, mode)),
+    )
+    .with_context(|| format!("could not set permissions on '{}'", tmp.display()))?;
+    if let Err(e) = std::fs::rename(&tmp, dest) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e).with_context(|| format!("could not write '{}'", dest.display()));
+    }
 
-    let clean_path = dest.strip_prefix("./").unwrap_or(&dest);
+    let clean_path = dest.strip_prefix("./").unwrap_or(dest);
     println!("[{}] {}", app, clean_path.display());
     Ok(())
 }
+
+/// Permission bits for a file about to be written to `dest`.
+///
+/// An existing file keeps its mode; a script whose existing copy lost its
+/// execute bits gets them back wherever the file is readable. Fresh files use
+/// the `mode` declared by their [`ConfigFile`].
+fn output_mode(dest: &Path, mode: u32) -> u32 {
+    match std::fs::metadata(dest) {
+        Ok(meta) if meta.is_file() => {
+            let existing = meta.permissions().mode() & 0o7777;
+            match mode & 0o111 != 0 {
+                true => existing | ((existing & 0o444) >> 2),
+                false => existing,
+            }
+        }
+        _ => mode,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    fn cfg(rel: &str, content: &str) -> WriteableTheme {
+        WriteableTheme {
+            content: content.into(),
+            rel_path: PathBuf::from(rel),
+            mode: ConfigFile::REGULAR,
+        }
+    }
+
+    fn script(rel: &str, content: &str) -> WriteableTheme {
+        WriteableTheme {
+            mode: ConfigFile::SCRIPT,
+            ..cfg(rel, content)
+        }
+    }
+
+    fn mode_of(path: &Path) -> u32 {
+        std::fs::metadata(path).unwrap().permissions().mode() & 0o7777
+    }
+
+    #[test]
+    fn writes_every_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = BackupStore::new(&tmp.path().join("backups"));
+        let root = tmp.path().join("config");
+        let (picom, polybar) = (App::Picom.into(), App::Polybar.into());
+        let rendered = vec![
+            (&picom, cfg("picom/picom.conf", "picom")),
+            (&polybar, script("polybar/scripts/tags.py", "tags")),
+        ];
+
+        let written = write_all(&rendered, &root, Some(store.begin("t").unwrap())).unwrap();
+        let names: Vec<&str> = written.apps.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, ["picom", "polybar"]);
+        assert_eq!(written.files[1], root.join("polybar/scripts/tags.py"));
+
+        let read = |rel: &str| std::fs::read_to_string(root.join(rel)).unwrap();
+        assert_eq!(read("picom/picom.conf"), "picom");
+        assert_eq!(read("polybar/scripts/tags.py"), "tags");
+        assert_eq!(store.list().unwrap().len(), 1);
+        assert_eq!(mode_of(&root.join("picom/picom.conf")), 0o644);
+        assert_eq!(mode_of(&root.join("polybar/scripts/tags.py")), 0o755);
+    }
+
+    #[test]
+    fn existing_modes_are_preserved() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().to_path_buf();
+        let conf = root.join("picom/picom.conf");
+        let autostart = root.join("herbstluftwm/autostart");
+        for (path, mode) in [(&conf, 0o600), (&autostart, 0o640)] {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "old").unwrap();
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode)).unwrap();
+        }
+
+        let (picom, hlwm) = (App::Picom.into(), App::Herbstluftwm.into());
+        let rendered = vec![
+            (&picom, cfg("picom/picom.conf", "new")),
+            (&hlwm, script("herbstluftwm/autostart", "new")),
+        ];
+        write_all(&rendered, &root, None).unwrap();
+
+        assert_eq!(mode_of(&conf), 0o600);
+        assert_eq!(mode_of(&autostart), 0o750);
+    }
+
+    #[test]
+    fn sections_gate_apps() {
+        let theme = Theme::from_toml_str(
+            r##"
+name = "gated"
+
+[ansi.normal]
+red = "#ff0000"
+
+[polybar]
+height = 24
+
+[picom]
+enabled = false
+"##,
+        )
+        .unwrap();
+        let mut apps: Vec<AppSpec> = App::iter().map(AppSpec::from).collect();
+        apps.push(AppSpec {
+            name: "dunst".into(),
+            sections: vec![],
+            files: vec![],
+            reload: vec![],
+        });
+
+        let (enabled, skipped) = enabled_apps(&theme, &apps).unwrap();
+        let names: Vec<&str> = enabled.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, ["polybar", "alacritty", "dunst"]);
+        let reasons: Vec<String> = skipped
+            .iter()
+            .map(|(app, reason)| format!("[{app}] {reason}"))
+            .collect();
+        assert_eq!(
+            reasons,
+            [
+                "[herbstluftwm] theme has no [herbstluftwm] section",
+                "[picom] picom.enabled is false",
+            ]
+        );
+        let alacritty = AppSpec::from(App::Alacritty);
+        let bare = Theme::from_toml_str("name = \"bare\"").unwrap();
+        assert_eq!(
+            enabled_apps(&bare, std::slice::from_ref(&alacritty))
+                .unwrap()
+                .1[0]
+                .1
+                .to_string(),
+            "theme has no [alacritty] or [ansi] section"
+        );
+    }
+
+    #[test]
+    fn scripts_are_declared_executable() {
+        let executable: Vec<PathBuf> = App::iter()
+            .flat_map(|app| app.get_config_file_rel_paths())
+            .filter(ConfigFile::is_executable)
+            .map(|f| f.rel_path)
+            .collect();
+        assert_eq!(
+            executable,
+            [
+                PathBuf::from("herbstluftwm/autostart"),
+                PathBuf::from("polybar/launch.py"),
+                PathBuf::from("polybar/scripts/tags.py"),
+            ]
+        );
+    }
+
+    #[test]
+    fn failed_write_rolls_back_earlier_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = BackupStore::new(&tmp.path().join("backups"));
+        let root = tmp.path().join("config");
+        std::fs::create_dir_all(root.join("picom")).unwrap();
+        std::fs::write(root.join("picom/picom.conf"), "before").unwrap();
+        // A non-empty directory where a file should go makes the rename fail.
+        std::fs::create_dir_all(root.join("polybar/config.ini/blocker")).unwrap();
+
+        let apps: Vec<AppSpec> = [App::Picom, App::Alacritty, App::Polybar]
+            .into_iter()
+            .map(AppSpec::from)
+            .collect();
+        let rendered = vec![
+            (&apps[0], cfg("picom/picom.conf", "after")),
+            (&apps[1], cfg("alacritty/alacritty.toml", "new")),
+            (&apps[2], cfg("polybar/config.ini", "bar")),
+        ];
+        let result = write_all(&rendered, &root, Some(store.begin("t").unwrap()));
+
+        assert!(result.is_err());
+        assert_eq!(
+            std::fs::read_to_string(root.join("picom/picom.conf")).unwrap(),
+            "before"
+        );
+        assert!(!root.join("alacritty/alacritty.toml").exists());
+        assert!(!root.join("polybar/.config.ini.axtc-tmp").exists());
+        assert!(store.list().unwrap().is_empty());
+    }
+}
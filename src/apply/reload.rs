@@ -0,0 +1,172 @@
+//! Reloads running applications after a successful apply.
+//!
+//! Each [`AppSpec`] carries an ordered list of [`ReloadStep`]s; the first step
+//! that succeeds wins and later steps are only fallbacks. The built-in apps
+//! default to:
+//!
+//! | App            | Steps                                                   |
+//! |----------------|---------------------------------------------------------|
+//! | herbstluftwm   | `herbstclient reload`                                   |
+//! | polybar        | `polybar-msg cmd restart`, then relaunch via `launch.py`|
+//! | picom          | `pkill -USR1 -x picom`, then `picom -b`                 |
+//! | alacritty      | none — alacritty live-reloads its config                |
+//!
+//! Apps declared in the axtc config file set their own steps with `reload`.
+//! Failures are reported per app and never abort the remaining reloads.
+
+use anyhow::{Context, Result, bail};
+use std::ffi::OsString;
+use std::process::{Command, Stdio};
+
+use super::{App, AppSpec};
+use crate::constants::CONFIG_DIR;
+
+/// A single command that attempts to reload an app.
+#[derive(Clone, Debug)]
+pub struct ReloadStep {
+    /// Executable name, resolved through `PATH`.
+    pub program: String,
+    /// Arguments passed to the executable.
+    pub args: Vec<String>,
+    /// Whether the command starts long-running children (e.g. relaunching a bar).
+    /// Detached steps have their output discarded instead of captured, so the
+    /// children do not keep the pipe open.
+    pub detached: bool,
+}
+
+impl ReloadStep {
+    pub(crate) fn new(program: &str, args: &[&str]) -> Self {
+        Self {
+            program: program.to_owned(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            detached: false,
+        }
+    }
+
+    pub(crate) fn detached(mut self) -> Self {
+        self.detached = true;
+        self
+    }
+}
+
+impl std::fmt::Display for ReloadStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.program)?;
+        for arg in &self.args {
+            write!(f, " {arg}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Result of reloading one app.
+#[derive(Debug)]
+pub enum ReloadOutcome {
+    /// The app has nothing to reload.
+    NotNeeded,
+    /// The given step succeeded.
+    Reloaded(ReloadStep),
+    /// Every step failed; holds the last error.
+    Failed(anyhow::Error),
+}
+
+impl App {
+    /// Commands that reload this app, in fallback order. Empty when the app
+    /// picks up config changes on its own.
+    pub fn reload_steps(&self) -> Vec<ReloadStep> {
+        match self {
+            Self::Herbstluftwm => vec![ReloadStep::new("herbstclient", &["reload"])],
+            Self::Polybar => {
+                let launch = CONFIG_DIR.join("polybar").join("launch.py");
+                vec![
+                    ReloadStep::new("polybar-msg", &["cmd", "restart"]),
+                    ReloadStep::new("python3", &[&launch.to_string_lossy()]).detached(),
+                ]
+            }
+            Self::Picom => vec![
+                ReloadStep::new("pkill", &["-USR1", "-x", "picom"]),
+                ReloadStep::new("picom", &["-b"]).detached(),
+            ],
+            Self::Alacritty => vec![],
+        }
+    }
+}
+
+/// Runs reload steps, optionally with an overridden `PATH`.
+#[derive(Default)]
+pub struct Reloader {
+    path: Option<OsString>,
+}
+
+impl Reloader {
+    /// Resolve reload executables through `path` instead of the inherited `PATH`.
+    pub fn with_path(path: impl Into<OsString>) -> Self {
+        Self {
+            path: Some(path.into()),
+        }
+    }
+
+    /// Reload a single app, trying each of its steps in order.
+    pub fn reload(&self, app: &AppSpec) -> ReloadOutcome {
+        if app.reload.is_empty() {
+            return ReloadOutcome::NotNeeded;
+        }
+        let mut last_err = None;
+        for step in &app.reload {
+            match self.run(step) {
+                Ok(()) => return ReloadOutcome::Reloaded(step.clone()),
+                Err(e) => last_err = Some(e),
+            }
+        }
+        ReloadOutcome::Failed(last_err.expect("at least one step was attempted"))
+    }
+
+    /// Reload every app in `apps`, printing one line per app.
+    ///
+    /// Returns the number of apps that failed to reload.
+    pub fn reload_all<'a>(&self, apps: impl IntoIterator<Item = &'a AppSpec>) -> usize {
+        let mut failures = 0;
+        for app in apps {
+            match self.reload(app) {
+                ReloadOutcome::NotNeeded => {}
+                ReloadOutcome::Reloaded(step) => println!("[{app}] reloaded ({step})"),
+                ReloadOutcome::Failed(e) => {
+                    failures += 1;
+                    println!("[{app}] reload failed: {e:#}");
+                }
+            }
+        }
+        failures
+    }
+
+    fn run(&self, step: &ReloadStep) -> Result<()> {
+        let mut cmd = Command::new(&step.program);
+        cmd.args(&step.args).stdin(Stdio::null());
+        if let Some(path) = &self.path {
+            cmd.env("PATH", path);
+        }
+
+        if step.detached {
+            cmd.stdout(Stdio::null()).stderr(Stdio::null());
+            let status = cmd
+                .status()
+                .with_context(|| format!("could not run `{step}`"))?;
+            if !status.success() {
+                bail!("`{step}` exited with {status}");
+            }
+            return Ok(());
+        }
+
+        let output = cmd
+            .output()
+            .with_context(|| format!("could not run `{step}`"))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            match stderr.trim() {
+                "" => bail!("`{step}` exited with {}", output.status),
+                msg => bail!("`{step}` exited with {}: {msg}", output.status),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+//! Narrowing an apply to some apps or files, for `--only` and `--skip`.
+//!
+//! A selector is either an app name (`alacritty`) or the path of one of an
+//! app's files relative to the config directory (`polybar/config.ini`).
+//! Unknown selectors are rejected with the closest known name as a suggestion.
+
+use anyhow::{Result, bail};
+use std::path::Path;
+
+use super::{AppSpec, ConfigFile};
+
+/// Keep the apps and files named by `only` (everything when empty), minus
+/// those named by `skip`. Apps left without files are dropped.
+pub fn select(apps: Vec<AppSpec>, only: &[String], skip: &[String]) -> Result<Vec<AppSpec>> {
+    for selector in only.iter().chain(skip) {
+        validate(&apps, selector)?;
+    }
+    let names = |app: &AppSpec, selectors: &[String]| selectors.contains(&app.name);
+    Ok(apps
+        .into_iter()
+        .filter_map(|mut app| {
+            if !only.is_empty() && !names(&app, only) {
+                app.files.retain(|f| is_named(f, only));
+            }
+            if names(&app, skip) {
+                return None;
+            }
+            app.files.retain(|f| !is_named(f, skip));
+            (!app.files.is_empty()).then_some(app)
+        })
+        .collect())
+}
+
+fn is_named(file: &ConfigFile, selectors: &[String]) -> bool {
+    selectors.iter().any(|s| Path::new(s) == file.rel_path)
+}
+
+fn validate(apps: &[AppSpec], selector: &str) -> Result<()> {
+    let candidates: Vec<String> = apps
+        .iter()
+        .flat_map(|app| {
+            std::iter::once(app.name.clone()).chain(
+                app.files
+                    .iter()
+                    .map(|f| f.rel_path.to_string_lossy().into_owned()),
+            )
+        })
+        .collect();
+    if candidates.iter().any(|c| c == selector) {
+        return Ok(());
+    }
+    match closest(selector, &candidates) {
+        Some(suggestion) => {
+            bail!("unknown app or file '{selector}'; did you mean '{suggestion}'?")
+        }
+        None => bail!(
+            "unknown app or file '{selector}'; known apps: {}",
+            apps.iter()
+                .map(|a| a.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// The candidate within a third of `word`'s length in edit distance, if any.
+fn closest<'a>(word: &str, candidates: &'a [String]) -> Option<&'a str> {
+    let max = (word.chars().count() / 3).max(1);
+    candidates
+        .iter()
+        .map(|c| (edit_distance(word, c), c))
+        .filter(|(d, _)| *d <= max)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, c)| c.as_str())
+}
+
+/// Levenshtein distance between `a` and `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cur = row[j + 1];
+            row[j + 1] = (prev + usize::from(ca != *cb)).min(row[j] + 1).min(cur + 1);
+            prev = cur;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apply::App;
+    use strum::IntoEnumIterator;
+
+    fn apps() -> Vec<AppSpec> {
+        App::iter().map(AppSpec::from).collect()
+    }
+
+    fn selected(only: &[&str], skip: &[&str]) -> Result<Vec<(String, Vec<String>)>> {
+        let owned = |s: &[&str]| s.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        Ok(select(apps(), &owned(only), &owned(skip))?
+            .into_iter()
+            .map(|app| {
+                let files = app
+                    .files
+                    .iter()
+                    .map(|f| f.rel_path.display().to_string())
+                    .collect();
+                (app.name, files)
+            })
+            .collect())
+    }
+
+    #[test]
+    fn only_keeps_named_apps_and_files() {
+        let apps = selected(&["alacritty", "polybar/config.ini"], &[]).unwrap();
+        assert_eq!(
+            apps,
+            [
+                ("polybar".into(), vec!["polybar/config.ini".into()]),
+                ("alacritty".into(), vec!["alacritty/alacritty.toml".into()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn skip_drops_apps_and_files() {
+        let apps = selected(&[], &["herbstluftwm", "polybar/launch.py"]).unwrap();
+        let names: Vec<&str> = apps.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["polybar", "alacritty", "picom"]);
+        assert_eq!(apps[0].1, ["polybar/config.ini", "polybar/scripts/tags.py"]);
+    }
+
+    #[test]
+    fn unknown_names_suggest_the_closest() {
+        let err = selected(&["alacrity"], &[]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown app or file 'alacrity'; did you mean 'alacritty'?"
+        );
+        let err = selected(&[], &["polybar/config.in"]).unwrap_err();
+        assert!(
+            err.to_string()
+                .ends_with("did you mean 'polybar/config.ini'?")
+        );
+        let err = selected(&["dunst"], &[]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown app or file 'dunst'; known apps: herbstluftwm, polybar, alacritty, picom"
+        );
+    }
+}
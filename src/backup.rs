@@ -0,0 +1,547 @@
+//! Backups taken during `axtc apply`, and restoring them.
+//!
+//! Every apply that overwrites or creates a config file records a numbered
+//! backup under [`BACKUPS_DIR`]:
+//!
+//! ```text
+//! ~/.config/axtc/backups/
+//! └── 3/
+//!     ├── manifest.toml
+//!     ├── herbstluftwm/autostart
+//!     └── polybar/scripts/tags.py
+//! ```
+//!
+//! The manifest names the applied theme, when it was applied, and every
+//! destination the apply touched along with the copy of its previous contents.
+//! A destination that did not exist before the apply has no backup copy;
+//! restoring removes it again.
+//!
+//! [`BACKUPS_DIR`]: crate::constants::BACKUPS_DIR
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::constants::BACKUPS_DIR;
+
+const MANIFEST_FILE: &str = "manifest.toml";
+
+/// Record of one apply, stored as `manifest.toml` in its backup directory.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Manifest {
+    /// Sequential backup id, also the name of the backup directory.
+    pub id: u32,
+    /// Name of the theme that was applied.
+    pub theme: String,
+    /// Seconds since the Unix epoch when the apply started.
+    pub timestamp: u64,
+    /// Every destination written by the apply.
+    #[serde(default)]
+    pub files: Vec<BackupEntry>,
+}
+
+/// One destination written during an apply.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BackupEntry {
+    /// Absolute path of the config file that was written.
+    pub dest: PathBuf,
+    /// Copy of the previous contents, relative to the backup directory.
+    /// Absent when the file did not exist before the apply.
+    pub backup: Option<PathBuf>,
+}
+
+impl Manifest {
+    /// Timestamp formatted as `YYYY-MM-DD HH:MM:SS UTC`.
+    pub fn formatted_time(&self) -> String {
+        format_utc(self.timestamp)
+    }
+}
+
+/// Directory of numbered backups.
+pub struct BackupStore {
+    root: PathBuf,
+}
+
+impl Default for BackupStore {
+    fn default() -> Self {
+        Self::new(BACKUPS_DIR.as_path())
+    }
+}
+
+impl BackupStore {
+    /// Use `root` as the backups directory.
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+        }
+    }
+
+    /// Start recording a backup for an apply of `theme`.
+    ///
+    /// Nothing is written to disk until the first file is recorded. The id
+    /// follows the highest numbered directory, with or without a manifest, so
+    /// a backup left behind by an interrupted apply is never reused.
+    pub fn begin(&self, theme: &str) -> Result<Snapshot> {
+        let id = self.highest_id()? + 1;
+        Ok(Snapshot {
+            dir: self.root.join(id.to_string()),
+            manifest: Manifest {
+                id,
+                theme: theme.to_owned(),
+                timestamp: now(),
+                files: vec![],
+            },
+        })
+    }
+
+    /// The largest numeric directory name under the backups directory, or `0`.
+    fn highest_id(&self) -> Result<u32> {
+        if !self.root.exists() {
+            return Ok(0);
+        }
+        let mut highest = 0;
+        for entry in std::fs::read_dir(&self.root)
+            .with_context(|| format!("could not read '{}'", self.root.display()))?
+        {
+            let entry = entry?;
+            if let Some(id) = entry.file_name().to_str().and_then(|n| n.parse().ok())
+                && entry.path().is_dir()
+            {
+                highest = highest.max(id);
+            }
+        }
+        Ok(highest)
+    }
+
+    /// All recorded backups, oldest first.
+    pub fn list(&self) -> Result<Vec<Manifest>> {
+        if !self.root.exists() {
+            return Ok(vec![]);
+        }
+        let mut manifests = vec![];
+        for entry in std::fs::read_dir(&self.root)
+            .with_context(|| format!("could not read '{}'", self.root.display()))?
+        {
+            let path = entry?.path().join(MANIFEST_FILE);
+            if !path.is_file() {
+                continue;
+            }
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("could not read '{}'", path.display()))?;
+            let manifest: Manifest = toml::from_str(&content)
+                .with_context(|| format!("invalid backup manifest '{}'", path.display()))?;
+            manifests.push(manifest);
+        }
+        manifests.sort_by_key(|m| m.id);
+        Ok(manifests)
+    }
+
+    /// Look up a backup by id, or the most recent one when `id` is `None`.
+    pub fn get(&self, id: Option<u32>) -> Result<Manifest> {
+        let manifests = self.list()?;
+        match id {
+            Some(id) => manifests
+                .into_iter()
+                .find(|m| m.id == id)
+                .with_context(|| format!("no backup with id {id}")),
+            None => manifests
+                .into_iter()
+                .next_back()
+                .context("no backups recorded yet"),
+        }
+    }
+
+    /// Put every file recorded in `manifest` back in place.
+    ///
+    /// All backup copies are staged next to their destinations first; only when
+    /// every copy succeeded are they renamed into place, so a failure leaves the
+    /// current configs untouched.
+    pub fn restore(&self, manifest: &Manifest) -> Result<()> {
+        restore_from(&self.root.join(manifest.id.to_string()), manifest)
+    }
+
+    /// Delete all but the `keep` most recent backups, returning the removed manifests.
+    pub fn prune(&self, keep: usize) -> Result<Vec<Manifest>> {
+        let mut manifests = self.list()?;
+        let excess = manifests.len().saturating_sub(keep);
+        let removed: Vec<Manifest> = manifests.drain(..excess).collect();
+        for m in &removed {
+            let dir = self.root.join(m.id.to_string());
+            std::fs::remove_dir_all(&dir)
+                .with_context(|| format!("could not remove '{}'", dir.display()))?;
+        }
+        Ok(removed)
+    }
+}
+
+/// A backup being recorded during an apply.
+pub struct Snapshot {
+    dir: PathBuf,
+    manifest: Manifest,
+}
+
+impl Snapshot {
+    /// Record that `dest` is about to be written, copying its current contents
+    /// to `rel` inside the backup directory if it exists.
+    ///
+    /// Returns the path of the backup copy, if one was made. The manifest is
+    /// rewritten after every call so an interrupted apply can still be restored.
+    pub fn record(&mut self, dest: &Path, rel: &Path) -> Result<Option<PathBuf>> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("could not create '{}'", self.dir.display()))?;
+
+        let backup = if dest.exists() {
+            let backup_path = self.dir.join(rel);
+            if let Some(parent) = backup_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::copy(dest, &backup_path)
+                .with_context(|| format!("could not backup '{}'", dest.display()))?;
+            Some(backup_path)
+        } else {
+            None
+        };
+
+        self.manifest.files.push(BackupEntry {
+            dest: dest.to_path_buf(),
+            backup: backup.as_ref().map(|_| rel.to_path_buf()),
+        });
+        self.save()?;
+        Ok(backup)
+    }
+
+    /// Undo every write recorded so far and discard this backup.
+    ///
+    /// Used when an apply fails part-way through, so the files it already
+    /// wrote go back to their previous contents.
+    pub fn rollback(self) -> Result<()> {
+        restore_from(&self.dir, &self.manifest)?;
+        if self.dir.exists() {
+            std::fs::remove_dir_all(&self.dir)
+                .with_context(|| format!("could not remove '{}'", self.dir.display()))?;
+        }
+        Ok(())
+    }
+
+    /// The manifest recorded so far.
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+
+    fn save(&self) -> Result<()> {
+        let path = self.dir.join(MANIFEST_FILE);
+        let content = toml::to_string(&self.manifest).context("could not serialize manifest")?;
+        std::fs::write(&path, content)
+            .with_context(|| format!("could not write '{}'", path.display()))
+    }
+}
+
+/// Put every file in `manifest` back from the backup directory `dir`.
+///
+/// Backup copies are staged next to their destinations first. The current
+/// files are then moved aside and replaced one by one; if any step fails, the
+/// ones already done are undone in reverse, so the configs end up either
+/// fully restored or exactly as they were.
+fn restore_from(dir: &Path, manifest: &Manifest) -> Result<()> {
+    let mut staged: Vec<(PathBuf, &Path)> = vec![];
+
+    for entry in &manifest.files {
+        let Some(backup) = &entry.backup else {
+            continue;
+        };
+        let tmp = staging_path(&entry.dest);
+        if let Err(e) = stage(&dir.join(backup), &tmp) {
+            for (tmp, _) in &staged {
+                let _ = std::fs::remove_file(tmp);
+            }
+            return Err(e);
+        }
+        staged.push((tmp, &entry.dest));
+    }
+
+    let removed: Vec<&Path> = manifest
+        .files
+        .iter()
+        .filter(|e| e.backup.is_none() && e.dest.is_file())
+        .map(|e| e.dest.as_path())
+        .collect();
+    // Each destination replaced so far, with where its previous file was moved.
+    let mut done: Vec<(&Path, Option<PathBuf>)> = vec![];
+    let result = staged
+        .iter()
+        .try_for_each(|(tmp, dest)| replace(dest, Some(tmp), &mut done))
+        .and_then(|()| {
+            removed
+                .iter()
+                .try_for_each(|dest| replace(dest, None, &mut done))
+        });
+
+    if let Err(e) = result {
+        for (dest, aside) in done.iter().rev() {
+            let _ = match aside {
+                Some(aside) => std::fs::rename(aside, dest),
+                None => std::fs::remove_file(dest),
+            };
+        }
+        for (tmp, _) in &staged {
+            let _ = std::fs::remove_file(tmp);
+        }
+        return Err(e);
+    }
+
+    for (dest, aside) in &done {
+        if let Some(aside) = aside {
+            let _ = std::fs::remove_file(aside);
+        }
+        match removed.contains(dest) {
+            true => println!("removed {}", dest.display()),
+            false => println!("restored {}", dest.display()),
+        }
+    }
+    Ok(())
+}
+
+/// Move the file at `dest` aside, recording it in `done`, then put `tmp` in
+/// its place, or leave it gone when `tmp` is `None`.
+fn replace<'a>(
+    dest: &'a Path,
+    tmp: Option<&Path>,
+    done: &mut Vec<(&'a Path, Option<PathBuf>)>,
+) -> Result<()> {
+    let aside = match dest.is_file() {
+        true => {
+            let aside = aside_path(dest);
+            std::fs::rename(dest, &aside)
+                .with_context(|| format!("could not move '{}' aside", dest.display()))?;
+            Some(aside)
+        }
+        false => None,
+    };
+    done.push((dest, aside));
+    if let Some(tmp) = tmp {
+        std::fs::rename(tmp, dest)
+            .with_context(|| format!("could not restore '{}'", dest.display()))?;
+    }
+    Ok(())
+}
+
+fn stage(backup: &Path, tmp: &Path) -> Result<()> {
+    if let Some(parent) = tmp.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::copy(backup, tmp)
+        .with_context(|| format!("could not stage '{}'", backup.display()))?;
+    Ok(())
+}
+
+fn staging_path(dest: &Path) -> PathBuf {
+    hidden_sibling(dest, "axtc-restore")
+}
+
+/// Where the file at `dest` is kept while a restore replaces it.
+fn aside_path(dest: &Path) -> PathBuf {
+    hidden_sibling(dest, "axtc-previous")
+}
+
+fn hidden_sibling(dest: &Path, suffix: &str) -> PathBuf {
+    let name = dest
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    dest.with_file_name(format!(".{name}.{suffix}"))
+}
+
+pub(crate) fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// Format seconds since the Unix epoch as a UTC date and time.
+pub(crate) fn format_utc(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    // Civil-from-days, Howard Hinnant's algorithm.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn snapshot_preserves_relative_paths() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = BackupStore::new(&tmp.path().join("backups"));
+        let dest = tmp.path().join("config/polybar/scripts/tags.py");
+        write(&dest, "old");
+
+        let mut snap = store.begin("neon").unwrap();
+        let backup = snap
+            .record(&dest, Path::new("polybar/scripts/tags.py"))
+            .unwrap();
+        assert_eq!(
+            backup.unwrap(),
+            tmp.path().join("backups/1/polybar/scripts/tags.py")
+        );
+
+        let manifests = store.list().unwrap();
+        assert_eq!(manifests.len(), 1);
+        assert_eq!(manifests[0].theme, "neon");
+        assert_eq!(manifests[0].files[0].dest, dest);
+    }
+
+    #[test]
+    fn ids_increase_per_apply() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = BackupStore::new(tmp.path());
+        let dest = tmp.path().join("a.conf");
+        for _ in 0..3 {
+            store
+                .begin("t")
+                .unwrap()
+                .record(&dest, Path::new("a.conf"))
+                .unwrap();
+        }
+        let ids: Vec<u32> = store.list().unwrap().iter().map(|m| m.id).collect();
+        assert_eq!(ids, [1, 2, 3]);
+        assert_eq!(store.get(None).unwrap().id, 3);
+        assert!(store.get(Some(7)).is_err());
+    }
+
+    #[test]
+    fn ids_skip_directories_without_manifest() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = BackupStore::new(tmp.path());
+        write(&tmp.path().join("4/a.conf"), "stale");
+
+        let mut snap = store.begin("t").unwrap();
+        assert_eq!(snap.manifest().id, 5);
+        snap.record(&tmp.path().join("b.conf"), Path::new("b.conf"))
+            .unwrap();
+        assert!(!tmp.path().join("5/a.conf").exists());
+    }
+
+    #[test]
+    fn restore_puts_back_every_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = BackupStore::new(&tmp.path().join("backups"));
+        let existing = tmp.path().join("config/picom/picom.conf");
+        let created = tmp.path().join("config/polybar/launch.py");
+        write(&existing, "before");
+
+        let mut snap = store.begin("neon").unwrap();
+        snap.record(&existing, Path::new("picom/picom.conf"))
+            .unwrap();
+        snap.record(&created, Path::new("polybar/launch.py"))
+            .unwrap();
+        write(&existing, "after");
+        write(&created, "new");
+
+        store.restore(&store.get(None).unwrap()).unwrap();
+        assert_eq!(std::fs::read_to_string(&existing).unwrap(), "before");
+        assert!(!created.exists());
+    }
+
+    #[test]
+    fn failed_restore_leaves_configs_untouched() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = BackupStore::new(&tmp.path().join("backups"));
+        let a = tmp.path().join("config/a.conf");
+        let b = tmp.path().join("config/b.conf");
+        write(&a, "a-before");
+        write(&b, "b-before");
+
+        let mut snap = store.begin("t").unwrap();
+        snap.record(&a, Path::new("a.conf")).unwrap();
+        snap.record(&b, Path::new("b.conf")).unwrap();
+        write(&a, "a-after");
+        std::fs::remove_file(tmp.path().join("backups/1/b.conf")).unwrap();
+
+        assert!(store.restore(&store.get(None).unwrap()).is_err());
+        assert_eq!(std::fs::read_to_string(&a).unwrap(), "a-after");
+        assert!(!staging_path(&a).exists());
+    }
+
+    #[test]
+    fn restore_failing_midway_is_undone() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = BackupStore::new(&tmp.path().join("backups"));
+        let a = tmp.path().join("config/a.conf");
+        let b = tmp.path().join("config/b.conf");
+        let created = tmp.path().join("config/c.conf");
+        write(&a, "a-before");
+        write(&b, "b-before");
+
+        let mut snap = store.begin("t").unwrap();
+        snap.record(&a, Path::new("a.conf")).unwrap();
+        snap.record(&created, Path::new("c.conf")).unwrap();
+        snap.record(&b, Path::new("b.conf")).unwrap();
+        write(&a, "a-after");
+        write(&created, "c-after");
+        // A directory where b.conf was makes its rename fail after a.conf is restored.
+        std::fs::remove_file(&b).unwrap();
+        write(&b.join("keep"), "");
+
+        assert!(store.restore(&store.get(None).unwrap()).is_err());
+        assert_eq!(std::fs::read_to_string(&a).unwrap(), "a-after");
+        assert_eq!(std::fs::read_to_string(&created).unwrap(), "c-after");
+        assert!(b.join("keep").exists());
+        let leftovers: Vec<_> = std::fs::read_dir(tmp.path().join("config"))
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(leftovers.len(), 3, "{leftovers:?}");
+    }
+
+    #[test]
+    fn prune_keeps_most_recent() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = BackupStore::new(tmp.path());
+        let dest = tmp.path().join("a.conf");
+        for _ in 0..4 {
+            store
+                .begin("t")
+                .unwrap()
+                .record(&dest, Path::new("a.conf"))
+                .unwrap();
+        }
+        let removed = store.prune(2).unwrap();
+        assert_eq!(removed.iter().map(|m| m.id).collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(
+            store
+                .list()
+                .unwrap()
+                .iter()
+                .map(|m| m.id)
+                .collect::<Vec<_>>(),
+            [3, 4]
+        );
+    }
+
+    #[test]
+    fn formats_utc_timestamps() {
+        assert_eq!(format_utc(0), "1970-01-01 00:00:00 UTC");
+        assert_eq!(format_utc(1_709_251_199), "2024-02-29 23:59:59 UTC");
+    }
+}
@@ -1,6 +1,9 @@
 use std::path::PathBuf;
 
-use clap::{Parser, Subcommand};
+use axtc::theme::check::Level;
+use axtc::theme::cvd::{DEFAULT_MIN_DISTANCE, Deficiency};
+use axtc::theme::export::ExportFormat;
+use clap::{Args, Parser, Subcommand};
 
 #[derive(Parser)]
 #[command(name = "axtc", about = "Arch/X theme changer")]
@@ -13,21 +16,198 @@ pub struct Cli {
 pub enum Command {
     /// Apply a theme by name or path
     Apply {
-        /// Name of the theme (must exist in ~/.config/axtc/themes/)
-        #[arg(required_unless_present = "file", conflicts_with = "file")]
-        theme: Option<String>,
-        /// Path to a theme TOML file
-        #[arg(long, short = 'f', conflicts_with = "theme")]
-        file: Option<PathBuf>,
+        #[command(flatten)]
+        source: ThemeSource,
+        #[command(flatten)]
+        selection: AppSelection,
         /// Render templates and write output to the current directory instead of the real config paths
         #[arg(long)]
         dry_run: bool,
+        /// Do not reload running applications after applying
+        #[arg(long)]
+        no_reload: bool,
+        /// Warn about theme keys that no template references
+        #[arg(long)]
+        strict: bool,
+    },
+    /// Show how applying a theme would change the current config files
+    ///
+    /// Exits 0 when nothing would change, 1 when something would, and 2 on errors.
+    Diff {
+        #[command(flatten)]
+        source: ThemeSource,
+        #[command(flatten)]
+        selection: AppSelection,
+    },
+    /// Create the axtc config directory and install the bundled themes and templates
+    Init {
+        /// Overwrite files that already exist
+        #[arg(long)]
+        force: bool,
+        /// Back up the current config files of every app, so the first apply can be restored
+        #[arg(long)]
+        snapshot: bool,
     },
-    /// List available themes
+    /// List available themes and their variants
     List,
+    /// Print a theme's palette as terminal color swatches
+    Preview {
+        /// Name of the theme (must exist in ~/.config/axtc/themes/)
+        theme: String,
+    },
     /// Create a new theme file from the base template
     New {
         /// Name for the new theme
         name: String,
+        /// Generate the palette from a PNG or JPEG wallpaper instead of copying the base template
+        #[arg(long, value_name = "PATH")]
+        from_image: Option<PathBuf>,
+    },
+    /// Restore the config files overwritten by a previous apply
+    Restore {
+        /// Restore the most recent backup (the default)
+        #[arg(long, conflicts_with = "id")]
+        last: bool,
+        /// Restore the backup with this id (see `axtc backups list`)
+        #[arg(long)]
+        id: Option<u32>,
+    },
+    /// Create a new theme from another tool's color scheme
+    Import {
+        #[command(subcommand)]
+        format: ImportFormat,
+    },
+    /// Print a theme's palette in another tool's format
+    Export {
+        /// Name of the theme (must exist in ~/.config/axtc/themes/)
+        theme: String,
+        /// Output format: base16, xresources, kitty, windows-terminal, gpl, or css
+        #[arg(long)]
+        format: ExportFormat,
+    },
+    /// Check a theme's text colors for WCAG contrast
+    ///
+    /// Exits 0 when every check passes, 1 when any fails, and 2 on errors.
+    Check {
+        /// Name of the theme (must exist in ~/.config/axtc/themes/)
+        theme: String,
+        /// Conformance level to require: AA (4.5:1) or AAA (7:1)
+        #[arg(long, default_value = "AA")]
+        level: Level,
+        /// Require this contrast ratio instead of the level's
+        #[arg(long, value_name = "RATIO")]
+        min_ratio: Option<f32>,
+        /// Do not check this color (e.g. normal.black); may be repeated
+        #[arg(long, value_name = "COLOR")]
+        ignore: Vec<String>,
+        /// Also report ANSI colors that become hard to tell apart with this
+        /// color vision deficiency: protanopia, deuteranopia or tritanopia
+        #[arg(long, value_name = "DEFICIENCY")]
+        cvd: Option<Deficiency>,
+        /// Smallest CIEDE2000 distance two simulated ANSI colors may have
+        #[arg(long, value_name = "DELTA_E", default_value_t = DEFAULT_MIN_DISTANCE, requires = "cvd")]
+        min_distance: f32,
+        /// Save the simulated palette as a new theme named <theme>-<deficiency>
+        #[arg(long, requires = "cvd")]
+        write_simulated: bool,
+    },
+    /// Create a new theme from the config files currently on disk
+    Snapshot {
+        /// Name for the new theme
+        name: String,
+        /// Read the configs from this directory instead of ~/.config
+        #[arg(long, value_name = "DIR")]
+        from: Option<PathBuf>,
+    },
+    /// Show the applied theme and any config files edited since
+    #[command(alias = "current")]
+    Status,
+    /// Inspect and manage backups taken during apply
+    Backups {
+        #[command(subcommand)]
+        command: BackupsCommand,
+    },
+    /// Inspect where templates are loaded from
+    Templates {
+        #[command(subcommand)]
+        command: TemplatesCommand,
     },
 }
+
+#[derive(Subcommand)]
+pub enum TemplatesCommand {
+    /// Show which layer each template resolves from: theme, user or embedded
+    Which {
+        /// Apps or files to look up (e.g. polybar/config.ini); all when omitted
+        #[arg(value_name = "APP|FILE")]
+        targets: Vec<String>,
+        /// Also search this theme's template directory
+        #[arg(long)]
+        theme: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BackupsCommand {
+    /// List recorded backups, oldest first
+    List,
+    /// Delete all but the most recent backups
+    Prune {
+        /// Number of backups to keep
+        #[arg(long)]
+        keep: usize,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ImportFormat {
+    /// Import a base16 or base24 scheme YAML file
+    Base16(ImportArgs),
+    /// Import the [colors] tables of an alacritty.toml
+    Alacritty(ImportArgs),
+    /// Import the color lines of a kitty.conf
+    Kitty(ImportArgs),
+    /// Import the color entries of an .Xresources file
+    Xresources(ImportArgs),
+}
+
+#[derive(Args)]
+pub struct ImportArgs {
+    /// Path to the scheme file
+    pub file: PathBuf,
+    /// Name for the new theme (defaults to the file name)
+    #[arg(long)]
+    pub name: Option<String>,
+}
+
+/// Which theme to load, shared by `apply` and `diff`.
+#[derive(Args)]
+pub struct ThemeSource {
+    /// Name of the theme (must exist in ~/.config/axtc/themes/)
+    #[arg(required_unless_present = "file", conflicts_with = "file")]
+    pub theme: Option<String>,
+    /// Path to a theme TOML file
+    #[arg(long, short = 'f', conflicts_with = "theme")]
+    pub file: Option<PathBuf>,
+    /// Merge the theme's [variants.<name>] table over it first
+    #[arg(long, value_name = "NAME")]
+    pub variant: Option<String>,
+}
+
+/// Which apps and files to render, shared by `apply` and `diff`.
+#[derive(Args)]
+pub struct AppSelection {
+    /// Only render these apps or files, comma-separated (e.g. alacritty,polybar/config.ini)
+    #[arg(long, value_delimiter = ',', value_name = "APP|FILE")]
+    pub only: Vec<String>,
+    /// Leave these apps or files untouched, comma-separated
+    #[arg(long, value_delimiter = ',', value_name = "APP|FILE")]
+    pub skip: Vec<String>,
+}
+
+impl AppSelection {
+    /// Whether some apps or files are left out.
+    pub fn is_partial(&self) -> bool {
+        !self.only.is_empty() || !self.skip.is_empty()
+    }
+}
@@ -0,0 +1,346 @@
+//! The axtc config file (`~/.config/axtc/config.toml`).
+//!
+//! Each `[[app]]` entry declares an app rendered by the same pipeline as the
+//! built-in ones. An entry whose `name` matches a built-in app (`herbstluftwm`,
+//! `polybar`, `alacritty`, `picom`) overrides only the keys it sets; any other
+//! name adds a new app, applied after the built-ins in file order.
+//!
+//! ```toml
+//! [[app]]
+//! name    = "dunst"
+//! section = "dunst"                  # only applied to themes with a [dunst] section
+//! reload  = "dunstctl reload"
+//! files   = [
+//!     { template = "dunst/dunstrc.tera", dest = "dunst/dunstrc" },
+//! ]
+//!
+//! [[app]]
+//! name   = "picom"
+//! reload = []                        # never reload picom
+//! ```
+//!
+//! | Key                 | Meaning                                                        |
+//! |---------------------|----------------------------------------------------------------|
+//! | `name`              | App name, used in output and to match a built-in               |
+//! | `section`           | Theme section that enables the app; without one, a new app is always applied |
+//! | `files`             | `template` (relative to the templates directory), `dest` (relative to `~/.config`), optional `executable` |
+//! | `reload`            | A command, or a list of commands tried in order until one succeeds |
+//!
+//! Reload commands are split on whitespace and are not run through a shell. A
+//! command that starts a long-running process can be written as a table,
+//! `{ command = "picom -b", detached = true }`, so its output is discarded
+//! instead of waited on.
+
+use anyhow::{Context, Result, bail, ensure};
+use serde::Deserialize;
+use std::path::{Component, Path, PathBuf};
+use strum::IntoEnumIterator;
+
+use crate::apply::reload::ReloadStep;
+use crate::apply::{App, AppSpec, ConfigFile};
+use crate::constants::CONFIG_FILE;
+
+/// Parsed contents of the axtc config file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// `[[app]]` entries, in file order.
+    #[serde(default, rename = "app")]
+    pub apps: Vec<AppConfig>,
+}
+
+/// One `[[app]]` entry.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AppConfig {
+    /// App name; matching a built-in app overrides it.
+    pub name: String,
+    /// Theme section that enables the app; replaces a built-in app's sections.
+    pub section: Option<String>,
+    /// Files to render. Replaces a built-in app's files when non-empty.
+    #[serde(default)]
+    pub files: Vec<FileConfig>,
+    /// Reload commands. An empty list disables reloading.
+    pub reload: Option<ReloadConfig>,
+}
+
+/// A template → destination mapping.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileConfig {
+    /// Template path, relative to the templates directory unless absolute.
+    pub template: PathBuf,
+    /// Destination, relative to the config directory.
+    pub dest: PathBuf,
+    /// Whether the written file should be executable.
+    #[serde(default)]
+    pub executable: bool,
+}
+
+/// The `reload` key: one command or a list of fallbacks.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum ReloadConfig {
+    /// A single command.
+    One(CommandConfig),
+    /// Commands tried in order until one succeeds.
+    Many(Vec<CommandConfig>),
+}
+
+/// A reload command, either as a plain string or with options.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum CommandConfig {
+    /// `"program arg…"`.
+    Line(String),
+    /// `{ command = "program arg…", detached = true }`.
+    Table {
+        /// `"program arg…"`.
+        command: String,
+        /// Whether the command starts long-running children.
+        #[serde(default)]
+        detached: bool,
+    },
+}
+
+impl Config {
+    /// Load the config file from its default location, or an empty config if it does not exist.
+    pub fn load() -> Result<Self> {
+        match CONFIG_FILE.exists() {
+            true => Self::load_from_path(&CONFIG_FILE),
+            false => Ok(Self::default()),
+        }
+    }
+
+    /// Load the config file at `path`.
+    pub fn load_from_path(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("could not read config file '{}'", path.display()))?;
+        Self::from_toml_str(&content)
+            .with_context(|| format!("failed to parse config file '{}'", path.display()))
+    }
+
+    /// Deserialize a config from TOML source.
+    pub fn from_toml_str(content: &str) -> Result<Self> {
+        let de = toml::Deserializer::parse(content)?;
+        Ok(serde_path_to_error::deserialize(de)?)
+    }
+
+    /// The apps to apply: built-in apps with any overrides, followed by new apps.
+    pub fn apps(&self) -> Result<Vec<AppSpec>> {
+        let mut apps: Vec<AppSpec> = App::iter().map(AppSpec::from).collect();
+        let mut declared = vec![];
+        for entry in &self.apps {
+            ensure!(!entry.name.trim().is_empty(), "app names must not be empty");
+            ensure!(
+                !declared.contains(&&entry.name),
+                "app '{}' is declared more than once",
+                entry.name
+            );
+            declared.push(&entry.name);
+
+            let spec = match apps.iter_mut().find(|a| a.name == entry.name) {
+                Some(spec) => spec,
+                None => {
+                    ensure!(
+                        !entry.files.is_empty(),
+                        "[{}] app declares no files",
+                        entry.name
+                    );
+                    apps.push(AppSpec {
+                        name: entry.name.clone(),
+                        sections: vec![],
+                        files: vec![],
+                        reload: vec![],
+                    });
+                    apps.last_mut().unwrap()
+                }
+            };
+            entry
+                .apply_to(spec)
+                .with_context(|| format!("[{}] invalid app", entry.name))?;
+        }
+        Ok(apps)
+    }
+}
+
+impl AppConfig {
+    fn apply_to(&self, spec: &mut AppSpec) -> Result<()> {
+        if let Some(section) = &self.section {
+            spec.sections = vec![section.clone()];
+        }
+        if !self.files.is_empty() {
+            spec.files = self
+                .files
+                .iter()
+                .map(FileConfig::to_config_file)
+                .collect::<Result<_>>()?;
+        }
+        if let Some(reload) = &self.reload {
+            let commands = match reload {
+                ReloadConfig::One(c) => std::slice::from_ref(c),
+                ReloadConfig::Many(c) => c.as_slice(),
+            };
+            spec.reload = commands
+                .iter()
+                .map(CommandConfig::to_step)
+                .collect::<Result<_>>()?;
+        }
+        Ok(())
+    }
+}
+
+impl FileConfig {
+    fn to_config_file(&self) -> Result<ConfigFile> {
+        let escapes = self
+            .dest
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+        if escapes || self.dest.file_name().is_none() {
+            bail!(
+                "destination '{}' must be a file path relative to the config directory",
+                self.dest.display()
+            );
+        }
+        Ok(ConfigFile {
+            template: self.template.clone(),
+            rel_path: self.dest.clone(),
+            mode: match self.executable {
+                true => ConfigFile::SCRIPT,
+                false => ConfigFile::REGULAR,
+            },
+        })
+    }
+}
+
+impl CommandConfig {
+    fn to_step(&self) -> Result<ReloadStep> {
+        let (line, detached) = match self {
+            Self::Line(line) => (line, false),
+            Self::Table { command, detached } => (command, *detached),
+        };
+        let mut words = line.split_whitespace();
+        let Some(program) = words.next() else {
+            bail!("reload commands must not be empty");
+        };
+        let args: Vec<&str> = words.collect();
+        let step = ReloadStep::new(program, &args);
+        Ok(match detached {
+            true => step.detached(),
+            false => step,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apps(toml: &str) -> Result<Vec<AppSpec>> {
+        Config::from_toml_str(toml)?.apps()
+    }
+
+    fn find<'a>(apps: &'a [AppSpec], name: &str) -> &'a AppSpec {
+        apps.iter().find(|a| a.name == name).unwrap()
+    }
+
+    #[test]
+    fn empty_config_yields_builtins() {
+        let names: Vec<String> = apps("").unwrap().into_iter().map(|a| a.name).collect();
+        assert_eq!(names, ["herbstluftwm", "polybar", "alacritty", "picom"]);
+    }
+
+    #[test]
+    fn new_apps_are_appended() {
+        let apps = apps(
+            r#"
+[[app]]
+name    = "dunst"
+section = "dunst"
+reload  = "dunstctl reload"
+files   = [{ template = "dunst/dunstrc.tera", dest = "dunst/dunstrc" }]
+"#,
+        )
+        .unwrap();
+        let dunst = apps.last().unwrap();
+        assert_eq!(dunst.name, "dunst");
+        assert_eq!(dunst.sections, ["dunst"]);
+        assert_eq!(dunst.files[0].template, PathBuf::from("dunst/dunstrc.tera"));
+        assert_eq!(dunst.files[0].rel_path, PathBuf::from("dunst/dunstrc"));
+        assert!(!dunst.files[0].is_executable());
+        assert_eq!(dunst.reload[0].to_string(), "dunstctl reload");
+    }
+
+    #[test]
+    fn overrides_keep_unset_builtin_keys() {
+        let apps = apps(
+            r#"
+[[app]]
+name   = "picom"
+reload = [{ command = "picom --daemon", detached = true }, "true"]
+"#,
+        )
+        .unwrap();
+        let picom = find(&apps, "picom");
+        assert_eq!(picom.files, App::Picom.get_config_file_rel_paths());
+        assert_eq!(picom.reload.len(), 2);
+        assert!(picom.reload[0].detached);
+        assert_eq!(picom.reload[0].args, ["--daemon"]);
+        assert_eq!(picom.reload[1].program, "true");
+    }
+
+    #[test]
+    fn empty_reload_disables_reloading() {
+        let apps = apps("[[app]]\nname = \"polybar\"\nreload = []\n").unwrap();
+        assert!(find(&apps, "polybar").reload.is_empty());
+    }
+
+    #[test]
+    fn builtin_files_can_be_replaced() {
+        let apps = apps(
+            r#"
+[[app]]
+name  = "herbstluftwm"
+files = [{ template = "/opt/hlwm.tera", dest = "herbstluftwm/autostart", executable = true }]
+"#,
+        )
+        .unwrap();
+        let files = &find(&apps, "herbstluftwm").files;
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].template, PathBuf::from("/opt/hlwm.tera"));
+        assert!(files[0].is_executable());
+    }
+
+    #[test]
+    fn destinations_must_stay_in_config_dir() {
+        for dest in ["/etc/passwd", "../outside", "a/../../b"] {
+            let toml = format!(
+                "[[app]]\nname = \"x\"\nfiles = [{{ template = \"x.tera\", dest = \"{dest}\" }}]\n"
+            );
+            let err = format!("{:#}", apps(&toml).unwrap_err());
+            assert!(err.contains("relative to the config directory"), "{err}");
+        }
+    }
+
+    #[test]
+    fn new_apps_need_files() {
+        let err = apps("[[app]]\nname = \"x\"\n").unwrap_err().to_string();
+        assert_eq!(err, "[x] app declares no files");
+    }
+
+    #[test]
+    fn duplicate_names_are_rejected() {
+        let err = apps("[[app]]\nname = \"picom\"\n[[app]]\nname = \"picom\"\n")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(err, "app 'picom' is declared more than once");
+    }
+
+    #[test]
+    fn unknown_keys_name_their_path() {
+        let err = Config::from_toml_str("[[app]]\nname = \"x\"\nreloadd = \"y\"\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with("app[0]"), "{err}");
+    }
+}
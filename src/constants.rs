@@ -1,6 +1,6 @@
 //! Global path constants derived from the XDG config directory.
 //!
-//! All three values are initialized on first access via [`LazyLock`]. If
+//! All values are initialized on first access via [`LazyLock`]. If
 //! [`dirs::config_dir`] cannot resolve a config directory the application
 //! panics immediately — there is no meaningful way to continue without it.
 
@@ -18,3 +18,15 @@ pub static TEMPLATES_DIR: LazyLock<PathBuf> =
 /// Directory where axtc reads theme TOML files (`$XDG_CONFIG_HOME/axtc/themes`).
 pub static THEMES_DIR: LazyLock<PathBuf> =
     LazyLock::new(|| CONFIG_DIR.join("axtc").join("themes"));
+
+/// Directory where axtc records backups taken during apply (`$XDG_CONFIG_HOME/axtc/backups`).
+pub static BACKUPS_DIR: LazyLock<PathBuf> =
+    LazyLock::new(|| CONFIG_DIR.join("axtc").join("backups"));
+
+/// The axtc config file declaring user-defined apps (`$XDG_CONFIG_HOME/axtc/config.toml`).
+pub static CONFIG_FILE: LazyLock<PathBuf> =
+    LazyLock::new(|| CONFIG_DIR.join("axtc").join("config.toml"));
+
+/// Record of the last apply, read by `axtc status` (`$XDG_CONFIG_HOME/axtc/state/current.toml`).
+pub static STATE_FILE: LazyLock<PathBuf> =
+    LazyLock::new(|| CONFIG_DIR.join("axtc").join("state").join("current.toml"));
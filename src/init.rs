@@ -0,0 +1,162 @@
+//! Setting up a fresh axtc config directory, for `axtc init`.
+//!
+//! Installs the bundled `template.toml`, the example themes and the default
+//! templates into [`THEMES_DIR`] and [`TEMPLATES_DIR`], and can take a first
+//! backup of the configs axtc is about to manage.
+//!
+//! [`THEMES_DIR`]: crate::constants::THEMES_DIR
+//! [`TEMPLATES_DIR`]: crate::constants::TEMPLATES_DIR
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::apply::AppSpec;
+use crate::backup::{BackupStore, Manifest};
+use crate::template::search::EMBEDDED;
+
+/// The base template and example themes in the repository's `themes/`
+/// directory, keyed by file name.
+const THEMES: &[(&str, &str)] = &[
+    ("template.toml", include_str!("../themes/template.toml")),
+    ("neon.toml", include_str!("../themes/neon.toml")),
+];
+
+/// Theme name recorded in the manifest of the backup taken by [`snapshot`].
+pub const SNAPSHOT_NAME: &str = "init";
+
+/// What happened to one bundled file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    /// The file did not exist and was written.
+    Created,
+    /// The file existed and was replaced (`--force`).
+    Overwritten,
+    /// The file existed and was left alone.
+    Kept,
+}
+
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            Self::Created => "created",
+            Self::Overwritten => "overwrote",
+            Self::Kept => "kept",
+        })
+    }
+}
+
+/// Create `themes_dir` and `templates_dir` and install the bundled themes and
+/// templates into them. Existing files are kept unless `force` is set.
+///
+/// Returns every bundled file's destination with what was done to it.
+pub fn install(
+    themes_dir: &Path,
+    templates_dir: &Path,
+    force: bool,
+) -> Result<Vec<(PathBuf, Action)>> {
+    let bundled = THEMES
+        .iter()
+        .map(|(name, content)| (themes_dir.join(name), content))
+        .chain(
+            EMBEDDED
+                .iter()
+                .map(|(name, content)| (templates_dir.join(name), content)),
+        );
+
+    for dir in [themes_dir, templates_dir] {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("could not create '{}'", dir.display()))?;
+    }
+    let mut installed = vec![];
+    for (dest, content) in bundled {
+        let action = match (dest.exists(), force) {
+            (false, _) => Action::Created,
+            (true, true) => Action::Overwritten,
+            (true, false) => Action::Kept,
+        };
+        if action != Action::Kept {
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("could not create '{}'", parent.display()))?;
+            }
+            std::fs::write(&dest, content)
+                .with_context(|| format!("could not write '{}'", dest.display()))?;
+        }
+        installed.push((dest, action));
+    }
+    Ok(installed)
+}
+
+/// Back up every existing config file of `apps` under `root` into `store`, so
+/// the first apply can be undone with `axtc restore`.
+///
+/// Returns the backup's manifest, or `None` when none of the files exist yet.
+pub fn snapshot(store: &BackupStore, apps: &[AppSpec], root: &Path) -> Result<Option<Manifest>> {
+    let mut snapshot = store.begin(SNAPSHOT_NAME)?;
+    for file in apps.iter().flat_map(|app| &app.files) {
+        let dest = root.join(&file.rel_path);
+        if dest.is_file() {
+            snapshot.record(&dest, &file.rel_path)?;
+        }
+    }
+    let manifest = snapshot.manifest();
+    Ok((!manifest.files.is_empty()).then(|| manifest.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apply::App;
+
+    #[test]
+    fn existing_files_are_kept_unless_forced() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (themes, templates) = (tmp.path().join("themes"), tmp.path().join("templates"));
+        std::fs::create_dir_all(&themes).unwrap();
+        std::fs::write(themes.join("neon.toml"), "name = \"mine\"").unwrap();
+
+        let installed = install(&themes, &templates, false).unwrap();
+        assert_eq!(installed.len(), THEMES.len() + EMBEDDED.len());
+        let kept: Vec<&PathBuf> = installed
+            .iter()
+            .filter(|(_, action)| *action == Action::Kept)
+            .map(|(path, _)| path)
+            .collect();
+        assert_eq!(kept, [&themes.join("neon.toml")]);
+        assert_eq!(
+            std::fs::read_to_string(themes.join("neon.toml")).unwrap(),
+            "name = \"mine\""
+        );
+        assert!(templates.join("polybar/scripts/tags.py.tera").is_file());
+
+        let installed = install(&themes, &templates, true).unwrap();
+        assert!(
+            installed
+                .iter()
+                .all(|(_, action)| *action == Action::Overwritten)
+        );
+        assert!(
+            std::fs::read_to_string(themes.join("neon.toml"))
+                .unwrap()
+                .contains("name = \"neon\"")
+        );
+    }
+
+    #[test]
+    fn snapshot_backs_up_existing_configs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (root, store) = (tmp.path().join("config"), tmp.path().join("backups"));
+        let apps = [AppSpec::from(App::Picom), AppSpec::from(App::Alacritty)];
+        let store = BackupStore::new(&store);
+        assert!(snapshot(&store, &apps, &root).unwrap().is_none());
+        assert!(store.list().unwrap().is_empty());
+
+        std::fs::create_dir_all(root.join("picom")).unwrap();
+        std::fs::write(root.join("picom/picom.conf"), "hand-tuned").unwrap();
+        let manifest = snapshot(&store, &apps, &root).unwrap().unwrap();
+        assert_eq!(manifest.theme, SNAPSHOT_NAME);
+        assert_eq!(manifest.files.len(), 1);
+        assert_eq!(manifest.files[0].dest, root.join("picom/picom.conf"));
+        assert_eq!(store.list().unwrap().len(), 1);
+    }
+}
@@ -6,8 +6,9 @@
 //!
 //! # Theme file structure
 //!
-//! Every field except `name` is optional. Omit an entire section to skip that
-//! application — `axtc` silently skips any app whose section is absent.
+//! Every field except `name` is optional. Omit an entire section, or set
+//! `enabled = false` in it, to skip that application — `axtc apply` lists each
+//! skipped app and why. Alacritty is also applied when only `[ansi]` is present.
 //!
 //! ```toml
 //! name        = "my-theme"
@@ -39,7 +40,9 @@
 //!
 //! # Tera templates
 //!
-//! Templates live at `~/.config/axtc/templates/<app>/<file>.tera`. The entire
+//! Templates live at `~/.config/axtc/templates/<app>/<file>.tera`, can be
+//! overridden per theme, and fall back to defaults built into the binary; see
+//! [`search`](crate::template::search). The entire
 //! [`Theme`] is serialized into the Tera context, so every field is reachable
 //! by its TOML path. Because all sections are optional, guard with
 //! `{% if section %}` before accessing nested fields, and use
@@ -64,15 +67,28 @@
 //! ```
 //!
 //! The full context key paths for each section are documented on the
-//! corresponding config struct in the [`config`] module.
+//! corresponding config struct in the [`config`] module. Color filters such as
+//! `lighten`, `mix`, and `alpha` are documented in the [`filters`] module.
+//!
+//! # Apps
+//!
+//! herbstluftwm, polybar, alacritty, and picom are built in. Further apps, or
+//! overrides for the built-in ones, are declared in `~/.config/axtc/config.toml`;
+//! see the [`crate::config`] module.
 //!
 //! [Tera]: https://keats.github.io/tera/
 //! [`Theme`]: crate::theme::Theme
 //! [`config`]: crate::theme::config
+//! [`filters`]: crate::template::filters
 
 #![deny(missing_docs)]
 
 pub mod apply;
+pub mod backup;
+pub mod config;
 pub mod constants;
+pub mod init;
+pub mod preview;
+pub mod state;
 pub mod template;
 pub mod theme;
@@ -1,30 +1,196 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 
-use axtc::constants::THEMES_DIR;
+use axtc::apply::reload::Reloader;
+use axtc::backup::BackupStore;
+use axtc::config::Config;
+use axtc::constants::{CONFIG_DIR, TEMPLATES_DIR, THEMES_DIR};
+use axtc::preview::{ColorMode, render as render_preview};
+use axtc::state::{State, theme_hash};
+use axtc::template::search::TemplateSearch;
+use axtc::theme::check::{Level, contrast_pairs};
+use axtc::theme::cvd::{Deficiency, confusable_pairs};
 
 mod cli;
-use cli::{Cli, Command};
+use cli::{
+    AppSelection, BackupsCommand, Cli, Command, ImportFormat, TemplatesCommand, ThemeSource,
+};
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
     match cli.command {
-        Command::Apply { theme, file, dry_run } => {
-            let loaded = match (theme, file) {
-                (Some(name), None) => axtc::theme::Theme::load(&name)?,
-                (None, Some(p)) => axtc::theme::Theme::load_from_path(&p)?,
-                _ => unreachable!(),
-            };
-            axtc::apply::apply(&loaded, dry_run)
+        Command::Apply {
+            source,
+            selection,
+            dry_run,
+            no_reload,
+            strict,
+        } => {
+            let (loaded, source) = load_theme(source)?;
+            let all_apps = Config::load()?.apps()?;
+            let apps =
+                axtc::apply::select::select(all_apps.clone(), &selection.only, &selection.skip)?;
+            let templates = TemplateSearch::for_theme(Some(&source));
+            if strict {
+                // Keys used only by apps left out with --only / --skip are still referenced.
+                for key in axtc::apply::unreferenced_keys(&loaded, &all_apps, &templates)? {
+                    println!("warning: '{key}' is not referenced by any template");
+                }
+            }
+            let written = axtc::apply::apply(&loaded, &apps, &templates, dry_run)?;
+            if !dry_run && !written.files.is_empty() {
+                let mut state = State::new(&loaded, Some(&source), &written.files)?;
+                if selection.is_partial()
+                    && let Some(previous) = State::load()?
+                {
+                    state.keep_unwritten(previous);
+                }
+                state.save()?;
+            }
+            if !dry_run && !no_reload {
+                Reloader::default().reload_all(written.apps);
+            }
+            Ok(())
         }
+        Command::Diff { source, selection } => exit_on_error(diff(source, &selection)),
+        Command::Init { force, snapshot } => init(force, snapshot),
         Command::List => list(),
-        Command::New { name } => new_theme(&name),
+        Command::Preview { theme } => {
+            let loaded = axtc::theme::Theme::load(&theme)?;
+            print!("{}", render_preview(&loaded, ColorMode::detect()));
+            Ok(())
+        }
+        Command::New { name, from_image } => match from_image {
+            Some(image) => new_theme_from_image(&name, &image),
+            None => new_theme(&name),
+        },
+        Command::Restore { last: _, id } => restore(id),
+        Command::Import { format } => import(format),
+        Command::Export { theme, format } => {
+            let loaded = axtc::theme::Theme::load(&theme)?;
+            print!("{}", axtc::theme::export::export(&loaded, format)?);
+            Ok(())
+        }
+        Command::Check {
+            theme,
+            level,
+            min_ratio,
+            ignore,
+            cvd,
+            min_distance,
+            write_simulated,
+        } => exit_on_error(check(
+            &theme,
+            level,
+            min_ratio,
+            &ignore,
+            cvd,
+            min_distance,
+            write_simulated,
+        )),
+        Command::Snapshot { name, from } => snapshot(&name, from),
+        Command::Status => status(),
+        Command::Backups { command } => match command {
+            BackupsCommand::List => list_backups(),
+            BackupsCommand::Prune { keep } => prune_backups(keep),
+        },
+        Command::Templates { command } => match command {
+            TemplatesCommand::Which { targets, theme } => which_templates(&targets, theme),
+        },
+    }
+}
+
+/// Load the theme `source` names, returning it with the file it was read from.
+fn load_theme(source: ThemeSource) -> Result<(axtc::theme::Theme, PathBuf)> {
+    let path = match (&source.theme, source.file) {
+        (Some(name), None) => THEMES_DIR.join(format!("{name}.toml")),
+        (None, Some(p)) => p,
+        _ => unreachable!(),
+    };
+    let mut loaded = match source.theme {
+        Some(name) => axtc::theme::Theme::load(&name)?,
+        None => axtc::theme::Theme::load_from_path(&path)?,
+    };
+    if let Some(variant) = source.variant {
+        loaded = loaded.with_variant(&variant)?;
+    }
+    Ok((loaded, path))
+}
+
+/// The configured apps, narrowed by `--only` / `--skip`.
+fn selected_apps(selection: &AppSelection) -> Result<Vec<axtc::apply::AppSpec>> {
+    axtc::apply::select::select(Config::load()?.apps()?, &selection.only, &selection.skip)
+}
+
+/// Exit code of commands that exit 1 to report findings, such as `diff` and
+/// `check`, when they fail instead.
+const ERROR_EXIT_CODE: i32 = 2;
+
+/// Pass `result` through, but exit with [`ERROR_EXIT_CODE`] on an error so it
+/// cannot be mistaken for a command's findings.
+fn exit_on_error(result: Result<()>) -> Result<()> {
+    if let Err(err) = result {
+        eprintln!("Error: {err:?}");
+        std::process::exit(ERROR_EXIT_CODE);
+    }
+    Ok(())
+}
+
+fn diff(source: ThemeSource, selection: &AppSelection) -> Result<()> {
+    let (theme, source) = load_theme(source)?;
+    let apps = selected_apps(selection)?;
+    let templates = TemplateSearch::for_theme(Some(&source));
+    let diffs = axtc::apply::diff::diff(&theme, &apps, &templates)?;
+    let color = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+    let changed: Vec<_> = diffs.iter().filter(|d| d.is_changed()).collect();
+    for d in &changed {
+        println!("[{}] {}", d.app, d.dest.display());
+        print!("{}", d.unified(color));
+    }
+    println!(
+        "{}: {} of {} file(s) would change",
+        theme.name,
+        changed.len(),
+        diffs.len()
+    );
+    if !changed.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn init(force: bool, snapshot: bool) -> Result<()> {
+    use axtc::init::Action;
+
+    let installed = axtc::init::install(&THEMES_DIR, &TEMPLATES_DIR, force)?;
+    for (path, action) in &installed {
+        println!("{action:<10}{}", path.display());
     }
+    if installed.iter().any(|(_, action)| *action == Action::Kept) {
+        println!("warning: existing files were kept; pass --force to overwrite them");
+    }
+    if snapshot {
+        let apps = Config::load()?.apps()?;
+        match axtc::init::snapshot(&BackupStore::default(), &apps, &CONFIG_DIR)? {
+            Some(m) => println!(
+                "recorded backup {} of {} existing config file(s)",
+                m.id,
+                m.files.len()
+            ),
+            None => println!("No existing config files to back up"),
+        }
+    }
+    Ok(())
 }
 
 fn list() -> Result<()> {
     if !THEMES_DIR.exists() {
-        anyhow::bail!("themes directory not found: {}", THEMES_DIR.display());
+        anyhow::bail!(
+            "themes directory not found: {} (run `axtc init` to create it)",
+            THEMES_DIR.display()
+        );
     }
     let mut themes: Vec<String> = std::fs::read_dir(THEMES_DIR.as_path())?
         .filter_map(|e| e.ok())
@@ -43,7 +209,13 @@ fn list() -> Result<()> {
         println!("No themes found in {}", THEMES_DIR.display());
     } else {
         for t in themes {
-            println!("{t}");
+            let variants = axtc::theme::Theme::load(&t)
+                .map(|theme| theme.variant_names().join(", "))
+                .unwrap_or_default();
+            match variants.is_empty() {
+                true => println!("{t}"),
+                false => println!("{t} (variants: {variants})"),
+            }
         }
     }
     Ok(())
@@ -62,3 +234,260 @@ fn new_theme(name: &str) -> Result<()> {
     println!("Created {}", dest_path.display());
     Ok(())
 }
+
+fn new_theme_from_image(name: &str, image: &Path) -> Result<()> {
+    ensure_new_theme(name)?;
+    write_new_theme(&axtc::theme::Theme::from_image(name, image)?)
+}
+
+fn import(format: ImportFormat) -> Result<()> {
+    use axtc::theme::import;
+
+    let (args, load): (_, fn(&str, &Path) -> Result<import::Imported>) = match format {
+        ImportFormat::Base16(args) => (args, |name, file| {
+            Ok(import::Imported {
+                theme: import::base16::load(name, file)?,
+                missing: vec![],
+            })
+        }),
+        ImportFormat::Alacritty(args) => (args, import::alacritty::load),
+        ImportFormat::Kitty(args) => (args, import::kitty::load),
+        ImportFormat::Xresources(args) => (args, import::xresources::load),
+    };
+    let name = theme_name(args.name, &args.file)?;
+    ensure_new_theme(&name)?;
+    let imported = load(&name, &args.file)?;
+    if !imported.missing.is_empty() {
+        println!(
+            "warning: the scheme does not set {}; they are left unset in the theme",
+            imported.missing.join(", ")
+        );
+    }
+    write_new_theme(&imported.theme)
+}
+
+/// The `--name` given on the command line, or the stem of the imported file.
+fn theme_name(name: Option<String>, file: &Path) -> Result<String> {
+    match name {
+        Some(name) => Ok(name),
+        None => file
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .with_context(|| format!("cannot derive a theme name from '{}'", file.display())),
+    }
+}
+
+fn ensure_new_theme(name: &str) -> Result<()> {
+    let dest_path = THEMES_DIR.join(format!("{name}.toml"));
+    anyhow::ensure!(!dest_path.exists(), "theme '{name}' already exists");
+    Ok(())
+}
+
+fn write_new_theme(theme: &axtc::theme::Theme) -> Result<()> {
+    let dest_path = THEMES_DIR.join(format!("{}.toml", theme.name));
+    std::fs::create_dir_all(THEMES_DIR.as_path())?;
+    std::fs::write(&dest_path, toml::to_string(theme)?)?;
+    println!("Created {}", dest_path.display());
+    Ok(())
+}
+
+fn snapshot(name: &str, from: Option<PathBuf>) -> Result<()> {
+    ensure_new_theme(name)?;
+    let root = from.unwrap_or_else(|| CONFIG_DIR.clone());
+    let captured = axtc::theme::snapshot::capture(name, &root)?;
+    anyhow::ensure!(
+        !captured.read.is_empty(),
+        "no app configs found under {}",
+        root.display()
+    );
+    for path in &captured.read {
+        println!("read {}", path.display());
+    }
+    for path in &captured.missing {
+        println!(
+            "warning: {} not found; its app is left out of the theme",
+            path.display()
+        );
+    }
+    write_new_theme(&captured.theme)
+}
+
+fn restore(id: Option<u32>) -> Result<()> {
+    let store = BackupStore::default();
+    let manifest = store.get(id)?;
+    println!(
+        "Restoring backup {} ({}, theme '{}')",
+        manifest.id,
+        manifest.formatted_time(),
+        manifest.theme
+    );
+    store.restore(&manifest)?;
+    State::clear()
+}
+
+fn status() -> Result<()> {
+    let Some(state) = State::load()? else {
+        println!("No applied theme recorded");
+        return Ok(());
+    };
+    match &state.variant {
+        Some(variant) => println!("Theme:    {} (variant {variant})", state.theme),
+        None => println!("Theme:    {}", state.theme),
+    }
+    if let Some(source) = &state.source {
+        println!("Source:   {}", source.display());
+    }
+    println!("Applied:  {}", state.formatted_time());
+
+    let drift = state.drift();
+    match drift.is_empty() {
+        true => println!("Files:    {} written, all unchanged", state.files.len()),
+        false => println!(
+            "Files:    {} written, {} changed since",
+            state.files.len(),
+            drift.len()
+        ),
+    }
+    for d in &drift {
+        println!("  {d}");
+    }
+
+    if let Some(source) = &state.source {
+        let current =
+            axtc::theme::Theme::load_from_path(source).and_then(|t| match &state.variant {
+                // A theme may name its base palette without defining it as a variant.
+                Some(v) if t.variants.contains_key(v) => t.with_variant(v),
+                _ => Ok(t),
+            });
+        match current.and_then(|t| theme_hash(&t)) {
+            Ok(hash) if hash != state.hash => println!(
+                "warning: theme '{}' has changed since it was applied",
+                state.theme
+            ),
+            Ok(_) => {}
+            Err(e) => println!("warning: could not reload theme '{}': {e:#}", state.theme),
+        }
+    }
+    Ok(())
+}
+
+fn check(
+    theme: &str,
+    level: Level,
+    min_ratio: Option<f32>,
+    ignore: &[String],
+    cvd: Option<Deficiency>,
+    min_distance: f32,
+    write_simulated: bool,
+) -> Result<()> {
+    let loaded = axtc::theme::Theme::load(theme)?;
+    let min = min_ratio.unwrap_or(level.min_ratio());
+    let pairs: Vec<_> = contrast_pairs(&loaded)
+        .into_iter()
+        .filter(|p| {
+            !ignore
+                .iter()
+                .any(|i| p.label.starts_with(&format!("{i} on ")))
+        })
+        .collect();
+
+    let mut failures = 0;
+    for pair in &pairs {
+        let ratio = pair.ratio();
+        let status = match ratio >= min {
+            true => "ok",
+            false => {
+                failures += 1;
+                "FAIL"
+            }
+        };
+        println!(
+            "{status:<4}  {:<48} {} on {}  {ratio:>5.2}:1",
+            pair.label, pair.foreground, pair.background
+        );
+    }
+    let requirement = match min_ratio {
+        Some(min) => format!("{min}:1"),
+        None => format!("{level} ({min}:1)"),
+    };
+    println!(
+        "{theme}: {failures} of {} pairs below {requirement}",
+        pairs.len()
+    );
+
+    if let Some(deficiency) = cvd {
+        let confusable: Vec<_> = confusable_pairs(&loaded, deficiency, min_distance)
+            .into_iter()
+            .filter(|p| !ignore.contains(&p.first) && !ignore.contains(&p.second))
+            .collect();
+        println!();
+        for pair in &confusable {
+            println!(
+                "FAIL  {:<48} ΔE {:>5.1} (normally {:.1})",
+                format!("{} vs {}", pair.first, pair.second),
+                pair.simulated_distance,
+                pair.distance
+            );
+        }
+        println!(
+            "{theme}: {} ANSI pair(s) closer than ΔE {min_distance} with {deficiency}",
+            confusable.len()
+        );
+        failures += confusable.len();
+        if write_simulated {
+            let simulated = deficiency.simulate_theme(&loaded)?;
+            ensure_new_theme(&simulated.name)?;
+            write_new_theme(&simulated)?;
+        }
+    }
+
+    if failures > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn list_backups() -> Result<()> {
+    let manifests = BackupStore::default().list()?;
+    if manifests.is_empty() {
+        println!("No backups recorded");
+    }
+    for m in manifests {
+        println!(
+            "{:>4}  {}  {:<16} {} file(s)",
+            m.id,
+            m.formatted_time(),
+            m.theme,
+            m.files.len()
+        );
+    }
+    Ok(())
+}
+
+fn prune_backups(keep: usize) -> Result<()> {
+    let removed = BackupStore::default().prune(keep)?;
+    for m in &removed {
+        println!("removed backup {} ({})", m.id, m.formatted_time());
+    }
+    if removed.is_empty() {
+        println!("Nothing to prune");
+    }
+    Ok(())
+}
+
+fn which_templates(targets: &[String], theme: Option<String>) -> Result<()> {
+    let theme_file = theme.map(|name| THEMES_DIR.join(format!("{name}.toml")));
+    if let Some(file) = &theme_file {
+        anyhow::ensure!(file.exists(), "theme not found at {}", file.display());
+    }
+    let templates = TemplateSearch::for_theme(theme_file.as_deref());
+    let apps = axtc::apply::select::select(Config::load()?.apps()?, targets, &[])?;
+    for file in apps.iter().flat_map(|app| &app.files) {
+        let template = file.template.display().to_string();
+        match templates.find(&file.template) {
+            Some(source) => println!("{template:<32} {source}"),
+            None => println!("{template:<32} not found"),
+        }
+    }
+    Ok(())
+}
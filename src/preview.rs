@@ -0,0 +1,199 @@
+//! Terminal preview of a theme's palette, used by `axtc preview`.
+//!
+//! Colors are drawn as 24-bit escape sequences when `COLORTERM` advertises
+//! truecolor support, and quantized to the nearest xterm 256-color entry
+//! otherwise.
+
+use crate::theme::{BrightColors, Color, NormalColors, Theme};
+
+const RESET: &str = "\x1b[0m";
+const SWATCH: &str = "        ";
+const SAMPLE: &str = " The quick brown fox jumps over the lazy dog ";
+
+/// How colors are encoded in escape sequences.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorMode {
+    /// 24-bit `38;2;r;g;b` sequences.
+    TrueColor,
+    /// Nearest xterm 256-color palette entry (`38;5;n`).
+    Xterm256,
+}
+
+impl ColorMode {
+    /// Pick a mode from the `COLORTERM` environment variable.
+    pub fn detect() -> Self {
+        match std::env::var("COLORTERM").as_deref() {
+            Ok("truecolor" | "24bit") => Self::TrueColor,
+            _ => Self::Xterm256,
+        }
+    }
+
+    fn fg(&self, c: Color) -> String {
+        match self {
+            Self::TrueColor => format!("\x1b[38;2;{};{};{}m", c.r, c.g, c.b),
+            Self::Xterm256 => format!("\x1b[38;5;{}m", c.to_xterm256()),
+        }
+    }
+
+    fn bg(&self, c: Color) -> String {
+        match self {
+            Self::TrueColor => format!("\x1b[48;2;{};{};{}m", c.r, c.g, c.b),
+            Self::Xterm256 => format!("\x1b[48;5;{}m", c.to_xterm256()),
+        }
+    }
+}
+
+/// Render the palette of `theme`: primary colors, the 16 ANSI colors, a text
+/// sample, and the polybar accents, each with its name and hex value.
+pub fn render(theme: &Theme, mode: ColorMode) -> String {
+    let ansi = theme.ansi.as_ref();
+    let primary = ansi.and_then(|a| a.primary.as_ref());
+    let normal = ansi.and_then(|a| a.normal.as_ref()).map(normal_colors);
+    let bright = ansi.and_then(|a| a.bright.as_ref()).map(bright_colors);
+    let background = primary.and_then(|p| p.background);
+    let foreground = primary.and_then(|p| p.foreground);
+
+    let mut lines = vec![match &theme.description {
+        Some(d) => format!("{} — {d}", theme.name),
+        None => theme.name.clone(),
+    }];
+
+    section(
+        &mut lines,
+        mode,
+        "primary",
+        &[
+            ("background", background),
+            ("foreground", foreground),
+            ("cursor", primary.and_then(|p| p.cursor)),
+        ],
+    );
+    section(&mut lines, mode, "normal", normal.as_deref().unwrap_or(&[]));
+    section(&mut lines, mode, "bright", bright.as_deref().unwrap_or(&[]));
+
+    lines.push(String::new());
+    lines.push("sample".to_owned());
+    let base = [
+        background.map(|c| mode.bg(c)),
+        foreground.map(|c| mode.fg(c)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<String>();
+    lines.push(format!("  {base}{SAMPLE}{RESET}"));
+    if let Some(normal) = normal {
+        let words = normal
+            .iter()
+            .map(|(name, color)| match color {
+                Some(c) => format!("{}{name} ", mode.fg(*c)),
+                None => format!("{base}{name} "),
+            })
+            .collect::<String>();
+        lines.push(format!("  {base} {words}{RESET}"));
+    }
+
+    let polybar = theme.polybar.as_ref();
+    section(
+        &mut lines,
+        mode,
+        "polybar",
+        &[
+            ("primary_color", polybar.and_then(|p| p.primary_color)),
+            ("background_alt", polybar.and_then(|p| p.background_alt)),
+        ],
+    );
+
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+fn section(
+    lines: &mut Vec<String>,
+    mode: ColorMode,
+    title: &str,
+    colors: &[(&str, Option<Color>)],
+) {
+    lines.push(String::new());
+    lines.push(title.to_owned());
+    for (name, color) in colors {
+        lines.push(match color {
+            Some(c) => format!("  {}{SWATCH}{RESET}  {name:<14} {c}", mode.bg(*c)),
+            None => format!("  {SWATCH}  {name:<14} (unset)"),
+        });
+    }
+}
+
+fn normal_colors(c: &NormalColors) -> Vec<(&'static str, Option<Color>)> {
+    vec![
+        ("black", c.black),
+        ("red", c.red),
+        ("green", c.green),
+        ("yellow", c.yellow),
+        ("blue", c.blue),
+        ("magenta", c.magenta),
+        ("cyan", c.cyan),
+        ("white", c.white),
+    ]
+}
+
+fn bright_colors(c: &BrightColors) -> Vec<(&'static str, Option<Color>)> {
+    vec![
+        ("black", c.black),
+        ("red", c.red),
+        ("green", c.green),
+        ("yellow", c.yellow),
+        ("blue", c.blue),
+        ("magenta", c.magenta),
+        ("cyan", c.cyan),
+        ("white", c.white),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn theme() -> Theme {
+        Theme::from_toml_str(
+            r##"
+name        = "preview"
+description = "Test palette"
+
+[ansi.primary]
+background = "#1a1a2e"
+foreground = "#e0e0e0"
+
+[ansi.normal]
+red = "#ff0000"
+
+[polybar]
+primary_color = "#ff8da1"
+"##,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn truecolor_swatches_show_hex_values() {
+        let out = render(&theme(), ColorMode::TrueColor);
+        assert!(out.starts_with("preview — Test palette\n"));
+        assert!(out.contains("\x1b[48;2;255;0;0m"));
+        assert!(out.contains("red            #ff0000"));
+        assert!(out.contains("primary_color  #ff8da1"));
+        assert!(out.contains("cursor         (unset)"));
+    }
+
+    #[test]
+    fn sample_is_drawn_fg_on_bg() {
+        let out = render(&theme(), ColorMode::TrueColor);
+        let sample = format!("\x1b[48;2;26;26;46m\x1b[38;2;224;224;224m{SAMPLE}{RESET}");
+        assert!(out.contains(&sample), "{out}");
+    }
+
+    #[test]
+    fn xterm256_fallback() {
+        let out = render(&theme(), ColorMode::Xterm256);
+        assert!(out.contains("\x1b[48;5;196m"));
+        assert!(!out.contains("48;2;"));
+    }
+}
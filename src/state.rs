@@ -0,0 +1,264 @@
+//! The record of the last apply, read by `axtc status`.
+//!
+//! Every apply that writes files replaces [`STATE_FILE`] with what it applied
+//! and a SHA-256 hash of each file it wrote:
+//!
+//! ```toml
+//! theme     = "neon"
+//! variant   = "light"
+//! source    = "/home/me/.config/axtc/themes/neon.toml"
+//! hash      = "3f1c…"
+//! timestamp = 1760000000
+//!
+//! [[files]]
+//! path = "/home/me/.config/picom/picom.conf"
+//! hash = "9a0b…"
+//! ```
+//!
+//! The theme hash covers the theme as rendered, after `extends`, references and
+//! the variant are resolved, so edits to a parent theme count as changes too.
+//!
+//! [`STATE_FILE`]: crate::constants::STATE_FILE
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+use crate::backup::{format_utc, now};
+use crate::constants::STATE_FILE;
+use crate::theme::Theme;
+
+/// What the last apply wrote.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct State {
+    /// Name of the applied theme.
+    pub theme: String,
+    /// Variant the theme was applied with, if any.
+    pub variant: Option<String>,
+    /// Theme file the theme was loaded from.
+    pub source: Option<PathBuf>,
+    /// Hash of the resolved theme; see [`theme_hash`].
+    pub hash: String,
+    /// Seconds since the Unix epoch when the apply finished.
+    pub timestamp: u64,
+    /// Every file written, in write order.
+    #[serde(default)]
+    pub files: Vec<FileState>,
+}
+
+/// A file written by the last apply.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct FileState {
+    /// Absolute path of the file.
+    pub path: PathBuf,
+    /// Hash of the contents axtc wrote.
+    pub hash: String,
+}
+
+/// A written file that no longer matches what axtc wrote.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Drift {
+    /// The file was edited after the apply.
+    Modified(PathBuf),
+    /// The file was deleted after the apply.
+    Missing(PathBuf),
+}
+
+impl std::fmt::Display for Drift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Modified(path) => write!(f, "modified  {}", path.display()),
+            Self::Missing(path) => write!(f, "missing   {}", path.display()),
+        }
+    }
+}
+
+impl State {
+    /// Record an apply of `theme`, loaded from `source`, that wrote `files`.
+    pub fn new(theme: &Theme, source: Option<&Path>, files: &[PathBuf]) -> Result<Self> {
+        Ok(Self {
+            theme: theme.name.clone(),
+            variant: theme.variant.clone(),
+            source: source.map(|s| s.canonicalize().unwrap_or_else(|_| s.to_path_buf())),
+            hash: theme_hash(theme)?,
+            timestamp: now(),
+            files: files
+                .iter()
+                .map(|path| {
+                    Ok(FileState {
+                        path: path.clone(),
+                        hash: file_hash(path)?,
+                    })
+                })
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    /// Carry over the files of `previous` that this apply did not rewrite, when
+    /// both applied the same theme and variant. Used after a partial apply
+    /// (`--only` / `--skip`) so the untouched files stay tracked.
+    pub fn keep_unwritten(&mut self, previous: State) {
+        if previous.theme != self.theme || previous.variant != self.variant {
+            return;
+        }
+        for file in previous.files {
+            if !self.files.iter().any(|f| f.path == file.path) {
+                self.files.push(file);
+            }
+        }
+    }
+
+    /// Read the state left by the last apply, if any.
+    pub fn load() -> Result<Option<Self>> {
+        Self::load_from(&STATE_FILE)
+    }
+
+    /// Read the state stored at `path`, if it exists.
+    pub fn load_from(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("could not read '{}'", path.display()))?;
+        toml::from_str(&content)
+            .map(Some)
+            .with_context(|| format!("invalid state file '{}'", path.display()))
+    }
+
+    /// Replace the stored state with this one.
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&STATE_FILE)
+    }
+
+    /// Write this state to `path`.
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("could not create '{}'", parent.display()))?;
+        }
+        let content = toml::to_string(self).context("could not serialize state")?;
+        std::fs::write(path, content)
+            .with_context(|| format!("could not write '{}'", path.display()))
+    }
+
+    /// Forget the stored state, e.g. after `axtc restore` put other files back.
+    pub fn clear() -> Result<()> {
+        match std::fs::remove_file(STATE_FILE.as_path()) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).with_context(|| format!("could not remove '{}'", STATE_FILE.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Written files whose contents no longer match their recorded hash.
+    pub fn drift(&self) -> Vec<Drift> {
+        self.files
+            .iter()
+            .filter_map(|f| match file_hash(&f.path) {
+                Ok(hash) if hash == f.hash => None,
+                Ok(_) => Some(Drift::Modified(f.path.clone())),
+                Err(_) => Some(Drift::Missing(f.path.clone())),
+            })
+            .collect()
+    }
+
+    /// Timestamp formatted as `YYYY-MM-DD HH:MM:SS UTC`.
+    pub fn formatted_time(&self) -> String {
+        format_utc(self.timestamp)
+    }
+}
+
+/// Hash of `theme` as applied: its serialized TOML after `extends`, references
+/// and the variant have been resolved.
+pub fn theme_hash(theme: &Theme) -> Result<String> {
+    let content = toml::to_string(theme).context("could not serialize theme")?;
+    Ok(hex(&Sha256::digest(content)))
+}
+
+fn file_hash(path: &Path) -> Result<String> {
+    let content =
+        std::fs::read(path).with_context(|| format!("could not read '{}'", path.display()))?;
+    Ok(hex(&Sha256::digest(content)))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edited_and_deleted_files_are_reported() {
+        let tmp = tempfile::tempdir().unwrap();
+        let files: Vec<PathBuf> = ["picom.conf", "autostart", "config.ini"]
+            .iter()
+            .map(|name| {
+                let path = tmp.path().join(name);
+                std::fs::write(&path, "written").unwrap();
+                path
+            })
+            .collect();
+        let theme = Theme::from_toml_str("name = \"neon\"").unwrap();
+        let state = State::new(&theme, None, &files).unwrap();
+        assert!(state.drift().is_empty());
+
+        std::fs::write(&files[0], "hand-edited").unwrap();
+        std::fs::remove_file(&files[2]).unwrap();
+        assert_eq!(
+            state.drift(),
+            [
+                Drift::Modified(files[0].clone()),
+                Drift::Missing(files[2].clone())
+            ]
+        );
+    }
+
+    #[test]
+    fn state_round_trips() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("state/current.toml");
+        assert_eq!(State::load_from(&path).unwrap(), None);
+
+        let theme = Theme::from_toml_str("name = \"neon\"\nvariant = \"light\"").unwrap();
+        let state = State::new(&theme, None, &[]).unwrap();
+        state.save_to(&path).unwrap();
+        let loaded = State::load_from(&path).unwrap().unwrap();
+        assert_eq!(loaded, state);
+        assert_eq!(loaded.variant.as_deref(), Some("light"));
+        assert_eq!(loaded.hash.len(), 64);
+    }
+
+    #[test]
+    fn partial_applies_keep_other_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let [a, b] = ["a", "b"].map(|name| {
+            let path = tmp.path().join(name);
+            std::fs::write(&path, name).unwrap();
+            path
+        });
+        let neon = Theme::from_toml_str("name = \"neon\"").unwrap();
+        let previous = State::new(&neon, None, &[a.clone(), b.clone()]).unwrap();
+
+        let mut state = State::new(&neon, None, std::slice::from_ref(&b)).unwrap();
+        state.keep_unwritten(previous.clone());
+        let paths: Vec<&PathBuf> = state.files.iter().map(|f| &f.path).collect();
+        assert_eq!(paths, [&b, &a]);
+
+        let other = Theme::from_toml_str("name = \"other\"").unwrap();
+        let mut state = State::new(&other, None, std::slice::from_ref(&b)).unwrap();
+        state.keep_unwritten(previous);
+        assert_eq!(state.files.len(), 1);
+    }
+
+    #[test]
+    fn theme_hash_tracks_content() {
+        let a = Theme::from_toml_str("name = \"t\"\n[picom]\nblur = true").unwrap();
+        let b = Theme::from_toml_str("name = \"t\"\n[picom]\nblur = false").unwrap();
+        assert_ne!(theme_hash(&a).unwrap(), theme_hash(&b).unwrap());
+        assert_eq!(theme_hash(&a).unwrap(), theme_hash(&a).unwrap());
+    }
+}
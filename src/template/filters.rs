@@ -0,0 +1,242 @@
+//! Color-manipulation filters registered on every [`Tera`] instance.
+//!
+//! Each filter takes a color string (any notation accepted by [`Color`]) and,
+//! unless noted otherwise, returns a normalized hex string so filters can be
+//! chained. Arguments are required unless a default is shown:
+//!
+//! | Filter                        | Result                                              |
+//! |-------------------------------|-----------------------------------------------------|
+//! | `lighten(amount=…)`           | HSL lightness raised by `amount` (`0.0`–`1.0`)      |
+//! | `darken(amount=…)`            | HSL lightness lowered by `amount`                   |
+//! | `saturate(amount=…)`          | HSL saturation raised by `amount`                   |
+//! | `desaturate(amount=…)`        | HSL saturation lowered by `amount`                  |
+//! | `mix(with=…, ratio=0.5)`      | Blend towards `with`; `ratio` is its share          |
+//! | `alpha(value=…)`              | Alpha channel replaced with `value` (`0.0`–`1.0`)   |
+//! | `invert`                      | Each channel inverted                               |
+//! | `complement`                  | Hue rotated by 180°                                 |
+//! | `to_rgb`                      | `rgb(r, g, b)`                                      |
+//! | `to_rgba_float`               | `rgba(r, g, b, a)` with channels in `0.000`–`1.000` |
+//! | `to_hex_no_hash`              | `rrggbb` (or `rrggbbaa`) without the leading `#`    |
+//!
+//! ```text
+//! hc set frame_bg_active_color '{{ ansi.normal.magenta | alpha(value=0.667) }}'
+//! background-alt = {{ ansi.primary.background | lighten(amount=0.05) }}
+//! ```
+
+use std::collections::HashMap;
+
+use tera::{Error, Result, Tera, Value};
+
+use crate::theme::Color;
+
+/// Register every color filter on `tera`.
+pub fn register(tera: &mut Tera) {
+    tera.register_filter("lighten", lighten);
+    tera.register_filter("darken", darken);
+    tera.register_filter("saturate", saturate);
+    tera.register_filter("desaturate", desaturate);
+    tera.register_filter("mix", mix);
+    tera.register_filter("alpha", alpha);
+    tera.register_filter("invert", invert);
+    tera.register_filter("complement", complement);
+    tera.register_filter("to_rgb", to_rgb);
+    tera.register_filter("to_rgba_float", to_rgba_float);
+    tera.register_filter("to_hex_no_hash", to_hex_no_hash);
+}
+
+fn color_input(filter: &str, value: &Value) -> Result<Color> {
+    let s = value.as_str().ok_or_else(|| {
+        Error::msg(format!(
+            "filter `{filter}` expects a color string, got {value}"
+        ))
+    })?;
+    s.parse()
+        .map_err(|e| Error::msg(format!("filter `{filter}`: {e}")))
+}
+
+fn color_arg(filter: &str, args: &HashMap<String, Value>, name: &str) -> Result<Color> {
+    let value = args
+        .get(name)
+        .ok_or_else(|| Error::msg(format!("filter `{filter}` requires a `{name}` argument")))?;
+    color_input(filter, value)
+}
+
+fn unit_arg(filter: &str, args: &HashMap<String, Value>, name: &str) -> Result<f32> {
+    let value = args
+        .get(name)
+        .ok_or_else(|| Error::msg(format!("filter `{filter}` requires a `{name}` argument")))?
+        .as_f64()
+        .ok_or_else(|| Error::msg(format!("filter `{filter}`: `{name}` must be a number")))?;
+    if !(0.0..=1.0).contains(&value) {
+        return Err(Error::msg(format!(
+            "filter `{filter}`: `{name}` must be between 0.0 and 1.0, got {value}"
+        )));
+    }
+    Ok(value as f32)
+}
+
+fn hex(color: Color) -> Result<Value> {
+    Ok(Value::String(color.to_hex()))
+}
+
+fn lighten(value: &Value, args: &HashMap<String, Value>) -> Result<Value> {
+    hex(color_input("lighten", value)?.lighten(unit_arg("lighten", args, "amount")?))
+}
+
+fn darken(value: &Value, args: &HashMap<String, Value>) -> Result<Value> {
+    hex(color_input("darken", value)?.darken(unit_arg("darken", args, "amount")?))
+}
+
+fn saturate(value: &Value, args: &HashMap<String, Value>) -> Result<Value> {
+    hex(color_input("saturate", value)?.saturate(unit_arg("saturate", args, "amount")?))
+}
+
+fn desaturate(value: &Value, args: &HashMap<String, Value>) -> Result<Value> {
+    hex(color_input("desaturate", value)?.desaturate(unit_arg("desaturate", args, "amount")?))
+}
+
+fn mix(value: &Value, args: &HashMap<String, Value>) -> Result<Value> {
+    let base = color_input("mix", value)?;
+    let other = color_arg("mix", args, "with")?;
+    let ratio = match args.get("ratio") {
+        Some(_) => unit_arg("mix", args, "ratio")?,
+        None => 0.5,
+    };
+    hex(base.mix(&other, ratio))
+}
+
+fn alpha(value: &Value, args: &HashMap<String, Value>) -> Result<Value> {
+    hex(color_input("alpha", value)?.with_alpha(unit_arg("alpha", args, "value")?))
+}
+
+fn invert(value: &Value, _: &HashMap<String, Value>) -> Result<Value> {
+    hex(color_input("invert", value)?.invert())
+}
+
+fn complement(value: &Value, _: &HashMap<String, Value>) -> Result<Value> {
+    hex(color_input("complement", value)?.complement())
+}
+
+fn to_rgb(value: &Value, _: &HashMap<String, Value>) -> Result<Value> {
+    let c = color_input("to_rgb", value)?;
+    Ok(Value::String(format!("rgb({}, {}, {})", c.r, c.g, c.b)))
+}
+
+fn to_rgba_float(value: &Value, _: &HashMap<String, Value>) -> Result<Value> {
+    let c = color_input("to_rgba_float", value)?;
+    let [r, g, b, a] = [c.r, c.g, c.b, c.a].map(|v| v as f32 / 255.0);
+    Ok(Value::String(format!(
+        "rgba({r:.3}, {g:.3}, {b:.3}, {a:.3})"
+    )))
+}
+
+fn to_hex_no_hash(value: &Value, _: &HashMap<String, Value>) -> Result<Value> {
+    let c = color_input("to_hex_no_hash", value)?;
+    Ok(Value::String(c.to_hex().trim_start_matches('#').to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use tera::Context;
+
+    use super::*;
+
+    fn render(src: &str) -> String {
+        let mut tera = Tera::default();
+        register(&mut tera);
+        tera.render_str(src, &Context::new()).unwrap()
+    }
+
+    #[test]
+    fn lighten_and_darken() {
+        assert_eq!(
+            render(r##"{{ "#808080" | lighten(amount=0.5) }}"##),
+            "#ffffff"
+        );
+        assert_eq!(
+            render(r##"{{ "#808080" | darken(amount=1.0) }}"##),
+            "#000000"
+        );
+    }
+
+    #[test]
+    fn saturate_and_desaturate() {
+        assert_eq!(
+            render(r##"{{ "#bf4040" | desaturate(amount=1.0) }}"##),
+            "#808080"
+        );
+        assert_eq!(
+            render(r##"{{ "#808080" | saturate(amount=0.5) }}"##),
+            "#c04141"
+        );
+    }
+
+    #[test]
+    fn mix_defaults_to_even_blend() {
+        assert_eq!(
+            render(r##"{{ "#000000" | mix(with="#ffffff") }}"##),
+            "#808080"
+        );
+        assert_eq!(
+            render(r##"{{ "#000000" | mix(with="white", ratio=0.0) }}"##),
+            "#000000"
+        );
+    }
+
+    #[test]
+    fn alpha_appends_channel() {
+        assert_eq!(
+            render(r##"{{ "#c11c84" | alpha(value=0.667) }}"##),
+            "#c11c84aa"
+        );
+        assert_eq!(
+            render(r##"{{ "#c11c84aa" | alpha(value=1.0) }}"##),
+            "#c11c84"
+        );
+    }
+
+    #[test]
+    fn invert_and_complement() {
+        assert_eq!(render(r##"{{ "#ff0000" | invert }}"##), "#00ffff");
+        assert_eq!(render(r##"{{ "#ff8000" | complement }}"##), "#007fff");
+    }
+
+    #[test]
+    fn format_conversions() {
+        assert_eq!(render(r##"{{ "#ff8000" | to_rgb }}"##), "rgb(255, 128, 0)");
+        assert_eq!(
+            render(r##"{{ "#ff000080" | to_rgba_float }}"##),
+            "rgba(1.000, 0.000, 0.000, 0.502)"
+        );
+        assert_eq!(render(r##"{{ "#FF8000" | to_hex_no_hash }}"##), "ff8000");
+    }
+
+    #[test]
+    fn filters_chain() {
+        assert_eq!(
+            render(
+                r##"{{ "#808080" | lighten(amount=0.5) | alpha(value=0.8) | to_hex_no_hash }}"##
+            ),
+            "ffffffcc"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        let mut tera = Tera::default();
+        register(&mut tera);
+        let ctx = Context::new();
+        assert!(
+            tera.render_str(r##"{{ "#ff1a3" | invert }}"##, &ctx)
+                .is_err()
+        );
+        assert!(
+            tera.render_str(r##"{{ "#ffffff" | lighten(amount=2) }}"##, &ctx)
+                .is_err()
+        );
+        assert!(
+            tera.render_str(r##"{{ "#ffffff" | lighten }}"##, &ctx)
+                .is_err()
+        );
+    }
+}
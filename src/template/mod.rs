@@ -0,0 +1,135 @@
+//! Tera template rendering.
+
+pub mod filters;
+pub mod search;
+
+use anyhow::{Context, Result};
+use std::path::Path;
+use tera::{Context as TeraContext, Tera};
+
+use crate::theme::Theme;
+
+/// Render a Tera template file against a [`Theme`], returning the result as a [`String`].
+///
+/// The template is read from disk each call; no caching is performed. See
+/// [`render_str`] for what the template can use.
+pub fn render(template_path: &Path, theme: &Theme) -> Result<String> {
+    let template_str = std::fs::read_to_string(template_path)
+        .with_context(|| format!("could not read template '{}'", template_path.display()))?;
+    render_str(&template_str, theme)
+}
+
+/// Render the Tera template source `template` against a [`Theme`].
+///
+/// The color filters from [`filters`] are available to every template, and
+/// `roles` holds [`Theme::resolved_roles`] rather than only the roles the
+/// theme sets.
+pub fn render_str(template: &str, theme: &Theme) -> Result<String> {
+    let mut tera = Tera::default();
+    filters::register(&mut tera);
+    tera.add_raw_template("t", template)
+        .context("could not parse template")?;
+
+    let mut context =
+        TeraContext::from_serialize(theme).context("could not build template context")?;
+    context.insert("roles", &theme.resolved_roles());
+
+    tera.render("t", &context)
+        .context("template rendering failed")
+}
+
+/// Dotted paths of the keys set in `theme` that none of the template `sources` mention.
+///
+/// A key counts as referenced when its full path (e.g. `rofi.width`) appears in
+/// a template as a whole word. Keys only read indirectly, such as by looping
+/// over a whole table, are reported as unreferenced. The `name`,
+/// `description` and `variant` metadata keys, and the `enabled` key of a
+/// section, are never reported. Keys under `variants.<name>` count as
+/// referenced when the path below the variant is.
+pub fn unreferenced_keys(theme: &Theme, sources: &[String]) -> Result<Vec<String>> {
+    let table = toml::Table::try_from(theme).context("could not serialize theme")?;
+    let mut keys = vec![];
+    collect_keys(&table, "", &mut keys);
+    keys.retain(|key| {
+        let path = match key.strip_prefix("variants.") {
+            Some(rest) => rest.split_once('.').map_or(rest, |(_, path)| path),
+            None => key,
+        };
+        let switch = path
+            .split_once('.')
+            .is_some_and(|(_, key)| key == crate::apply::ENABLED_KEY);
+        !matches!(path, "name" | "description" | "variant")
+            && !switch
+            && !sources.iter().any(|src| mentions(src, path))
+    });
+    Ok(keys)
+}
+
+fn collect_keys(table: &toml::Table, prefix: &str, keys: &mut Vec<String>) {
+    for (key, value) in table {
+        let path = match prefix {
+            "" => key.clone(),
+            _ => format!("{prefix}.{key}"),
+        };
+        match value {
+            toml::Value::Table(t) => collect_keys(t, &path, keys),
+            _ => keys.push(path),
+        }
+    }
+}
+
+/// Whether `path` occurs in `src` without being part of a longer identifier or path.
+fn mentions(src: &str, path: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    src.match_indices(path).any(|(i, _)| {
+        let before = src[..i].chars().next_back();
+        let after = src[i + path.len()..].chars().next();
+        !before.is_some_and(|c| is_ident(c) || c == '.') && !after.is_some_and(is_ident)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unreferenced_keys_are_reported() {
+        let theme = Theme::from_toml_str(
+            r#"
+name = "strict"
+
+[rofi]
+width  = 600
+height = 400
+
+[polybar]
+height     = 24
+height_alt = 30
+"#,
+        )
+        .unwrap();
+        let sources = vec![
+            "{{ rofi.width }} {{ polybar.height | default(value=24) }}".to_owned(),
+            "{{ rofi.heights }} {{ xpolybar.height_alt }}".to_owned(),
+        ];
+        let keys = unreferenced_keys(&theme, &sources).unwrap();
+        assert_eq!(keys, ["polybar.height_alt", "rofi.height"]);
+    }
+
+    #[test]
+    fn variant_keys_are_checked_below_the_variant() {
+        let theme = Theme::from_toml_str(
+            r#"
+name    = "strict"
+variant = "dark"
+
+[variants.light.rofi]
+width  = 600
+height = 400
+"#,
+        )
+        .unwrap();
+        let keys = unreferenced_keys(&theme, &["{{ rofi.width }}".to_owned()]).unwrap();
+        assert_eq!(keys, ["variants.light.rofi.height"]);
+    }
+}
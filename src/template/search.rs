@@ -0,0 +1,205 @@
+//! Where templates are looked up, from most to least specific:
+//!
+//! 1. the theme's own template directory, `templates/` inside a directory
+//!    named after the theme file (`themes/neon/templates/` for
+//!    `themes/neon.toml`);
+//! 2. the user's [`TEMPLATES_DIR`];
+//! 3. the default templates built into the binary.
+//!
+//! The first layer holding a template wins, so a theme or user only needs to
+//! copy the templates they want to change.
+//!
+//! [`TEMPLATES_DIR`]: crate::constants::TEMPLATES_DIR
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::constants::TEMPLATES_DIR;
+
+/// The templates in the repository's `templates/` directory, keyed by their
+/// path relative to it.
+pub(crate) const EMBEDDED: &[(&str, &str)] = &[
+    (
+        "alacritty/alacritty.toml.tera",
+        include_str!("../../templates/alacritty/alacritty.toml.tera"),
+    ),
+    (
+        "herbstluftwm/autostart.tera",
+        include_str!("../../templates/herbstluftwm/autostart.tera"),
+    ),
+    (
+        "picom/picom.conf.tera",
+        include_str!("../../templates/picom/picom.conf.tera"),
+    ),
+    (
+        "polybar/config.ini.tera",
+        include_str!("../../templates/polybar/config.ini.tera"),
+    ),
+    (
+        "polybar/launch.py.tera",
+        include_str!("../../templates/polybar/launch.py.tera"),
+    ),
+    (
+        "polybar/scripts/tags.py.tera",
+        include_str!("../../templates/polybar/scripts/tags.py.tera"),
+    ),
+];
+
+/// Where a template was found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    /// A file in the theme's template directory.
+    Theme(PathBuf),
+    /// A file in the user's templates directory, or an absolute template path
+    /// from the config file.
+    User(PathBuf),
+    /// A default template built into axtc.
+    Embedded(&'static str),
+}
+
+impl Source {
+    /// Name of the layer the template came from: `theme`, `user` or `embedded`.
+    pub fn layer(&self) -> &'static str {
+        match self {
+            Self::Theme(_) => "theme",
+            Self::User(_) => "user",
+            Self::Embedded(_) => "embedded",
+        }
+    }
+
+    /// The template's contents.
+    pub fn read(&self) -> Result<String> {
+        match self {
+            Self::Theme(path) | Self::User(path) => std::fs::read_to_string(path)
+                .with_context(|| format!("could not read template '{}'", path.display())),
+            Self::Embedded(content) => Ok((*content).to_owned()),
+        }
+    }
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Theme(path) | Self::User(path) => {
+                write!(f, "{:<9}{}", self.layer(), path.display())
+            }
+            Self::Embedded(_) => f.write_str(self.layer()),
+        }
+    }
+}
+
+/// The template directories searched for one apply.
+#[derive(Clone, Debug)]
+pub struct TemplateSearch {
+    theme_dir: Option<PathBuf>,
+    user_dir: PathBuf,
+}
+
+impl TemplateSearch {
+    /// Search the template directory of the theme loaded from `theme_file`,
+    /// then [`TEMPLATES_DIR`], then the built-in templates.
+    pub fn for_theme(theme_file: Option<&Path>) -> Self {
+        let theme_dir = theme_file.and_then(|file| {
+            let stem = file.file_stem()?;
+            Some(file.with_file_name(stem).join("templates"))
+        });
+        Self::with_dirs(theme_dir, TEMPLATES_DIR.clone())
+    }
+
+    /// Search `theme_dir`, if any, then `user_dir`, then the built-in templates.
+    pub fn with_dirs(theme_dir: Option<PathBuf>, user_dir: PathBuf) -> Self {
+        Self {
+            theme_dir,
+            user_dir,
+        }
+    }
+
+    /// Find `template`, a path relative to a templates directory. An absolute
+    /// path is only looked up as itself.
+    pub fn find(&self, template: &Path) -> Option<Source> {
+        if template.is_absolute() {
+            return template
+                .is_file()
+                .then(|| Source::User(template.to_path_buf()));
+        }
+        if let Some(path) = self
+            .theme_dir
+            .as_ref()
+            .map(|dir| dir.join(template))
+            .filter(|p| p.is_file())
+        {
+            return Some(Source::Theme(path));
+        }
+        let path = self.user_dir.join(template);
+        if path.is_file() {
+            return Some(Source::User(path));
+        }
+        EMBEDDED
+            .iter()
+            .find(|(name, _)| Path::new(name) == template)
+            .map(|(_, content)| Source::Embedded(content))
+    }
+}
+
+impl Default for TemplateSearch {
+    fn default() -> Self {
+        Self::for_theme(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apply::App;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn every_builtin_template_is_embedded() {
+        let tmp = tempfile::tempdir().unwrap();
+        let search = TemplateSearch::with_dirs(None, tmp.path().to_path_buf());
+        for file in App::iter().flat_map(|app| app.get_config_file_rel_paths()) {
+            let found = search.find(&file.template);
+            assert!(
+                matches!(found, Some(Source::Embedded(_))),
+                "{} is not embedded",
+                file.template.display()
+            );
+        }
+        assert_eq!(EMBEDDED.len(), 6);
+    }
+
+    #[test]
+    fn closer_layers_win() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (theme_dir, user_dir) = (tmp.path().join("theme"), tmp.path().join("user"));
+        for (dir, file) in [
+            (&theme_dir, "picom/picom.conf.tera"),
+            (&user_dir, "picom/picom.conf.tera"),
+            (&user_dir, "polybar/config.ini.tera"),
+        ] {
+            let path = dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, file).unwrap();
+        }
+        let search = TemplateSearch::with_dirs(Some(theme_dir.clone()), user_dir.clone());
+
+        let layer = |t: &str| search.find(Path::new(t)).map(|s| s.layer());
+        assert_eq!(layer("picom/picom.conf.tera"), Some("theme"));
+        assert_eq!(layer("polybar/config.ini.tera"), Some("user"));
+        assert_eq!(layer("alacritty/alacritty.toml.tera"), Some("embedded"));
+        assert_eq!(layer("dunst/dunstrc.tera"), None);
+        assert_eq!(
+            search.find(Path::new("picom/picom.conf.tera")),
+            Some(Source::Theme(theme_dir.join("picom/picom.conf.tera")))
+        );
+    }
+
+    #[test]
+    fn theme_dir_is_named_after_the_theme_file() {
+        let search = TemplateSearch::for_theme(Some(Path::new("/t/themes/neon.toml")));
+        assert_eq!(
+            search.theme_dir.as_deref(),
+            Some(Path::new("/t/themes/neon/templates"))
+        );
+    }
+}
@@ -0,0 +1,162 @@
+//! Readability checks for a theme's colors, used by `axtc check`.
+//!
+//! Contrast is the WCAG 2.x ratio between two colors' relative luminance,
+//! from `1:1` (identical) to `21:1` (black on white). The pairs checked are:
+//!
+//! | Pair                                    | Where it shows up                 |
+//! |-----------------------------------------|-----------------------------------|
+//! | `foreground` on `background`            | terminal text                     |
+//! | each `ansi.normal.*` / `ansi.bright.*` on `background` | colored terminal output |
+//! | `polybar.primary_color` on `background` | polybar module labels             |
+//! | `polybar.primary_color` on `polybar.background_alt` | the active workspace label |
+//!
+//! `background` is `ansi.primary.background`, which polybar also uses for the
+//! bar. Pairs with an unset color are skipped.
+
+use super::import::{ANSI_NAMES, Slots};
+use super::{Color, Theme};
+
+/// A WCAG conformance level for normal-size text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "UPPERCASE", ascii_case_insensitive)]
+pub enum Level {
+    /// At least 4.5:1.
+    Aa,
+    /// At least 7:1.
+    Aaa,
+}
+
+impl Level {
+    /// Minimum contrast ratio for this level.
+    pub fn min_ratio(&self) -> f32 {
+        match self {
+            Self::Aa => 4.5,
+            Self::Aaa => 7.0,
+        }
+    }
+}
+
+/// Two colors that are drawn on top of each other.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContrastPair {
+    /// What the pair is, e.g. `bright.black on background`.
+    pub label: String,
+    /// The color drawn on top (usually text).
+    pub foreground: Color,
+    /// The color underneath.
+    pub background: Color,
+}
+
+impl ContrastPair {
+    /// WCAG contrast ratio between the two colors.
+    pub fn ratio(&self) -> f32 {
+        self.foreground.contrast(&self.background)
+    }
+}
+
+/// Every contrast pair `theme` sets both colors for, in the order listed in the
+/// [module docs](self).
+pub fn contrast_pairs(theme: &Theme) -> Vec<ContrastPair> {
+    let slots = theme
+        .ansi
+        .as_ref()
+        .map(Slots::from_ansi)
+        .unwrap_or_default();
+    let polybar = theme.polybar.as_ref();
+    let accent = polybar.and_then(|p| p.primary_color);
+
+    let mut pairs = vec![(
+        "foreground",
+        slots.foreground,
+        "background",
+        slots.background,
+    )];
+    let names: Vec<String> = (0..16)
+        .map(|i| match i < 8 {
+            true => format!("normal.{}", ANSI_NAMES[i]),
+            false => format!("bright.{}", ANSI_NAMES[i - 8]),
+        })
+        .collect();
+    for (name, color) in names.iter().zip(slots.indexed) {
+        pairs.push((name.as_str(), color, "background", slots.background));
+    }
+    pairs.push((
+        "polybar.primary_color",
+        accent,
+        "background",
+        slots.background,
+    ));
+    pairs.push((
+        "polybar.primary_color",
+        accent,
+        "polybar.background_alt",
+        polybar.and_then(|p| p.background_alt),
+    ));
+
+    pairs
+        .into_iter()
+        .filter_map(|(fg_name, fg, bg_name, bg)| {
+            Some(ContrastPair {
+                label: format!("{fg_name} on {bg_name}"),
+                foreground: fg?,
+                background: bg?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pairs_cover_text_ansi_and_polybar() {
+        let theme = Theme::from_toml_str(
+            r##"
+name = "check"
+
+[ansi.primary]
+background = "#000000"
+foreground = "#ffffff"
+
+[ansi.normal]
+red = "#ff0000"
+
+[ansi.bright]
+black = "#222222"
+
+[polybar]
+primary_color  = "#ff8da1"
+background_alt = "#2b1046"
+"##,
+        )
+        .unwrap();
+        let pairs = contrast_pairs(&theme);
+        let labels: Vec<&str> = pairs.iter().map(|p| p.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            [
+                "foreground on background",
+                "normal.red on background",
+                "bright.black on background",
+                "polybar.primary_color on background",
+                "polybar.primary_color on polybar.background_alt",
+            ]
+        );
+        assert!((pairs[0].ratio() - 21.0).abs() < 0.01);
+        assert!(pairs[2].ratio() < Level::Aa.min_ratio());
+    }
+
+    #[test]
+    fn pairs_need_both_colors() {
+        let theme =
+            Theme::from_toml_str("name = \"t\"\n[ansi.normal]\nred = \"#ff0000\"\n").unwrap();
+        assert!(contrast_pairs(&theme).is_empty());
+    }
+
+    #[test]
+    fn levels_parse_case_insensitively() {
+        assert_eq!("aaa".parse::<Level>().unwrap(), Level::Aaa);
+        assert_eq!(Level::Aa.to_string(), "AA");
+    }
+}
@@ -0,0 +1,682 @@
+//! Validated color values used throughout the theme config.
+//!
+//! A [`Color`] is parsed once when the theme is loaded, so a typo such as
+//! `"#ff1a3"` is reported against the offending TOML key instead of surfacing
+//! later as a broken app config. Accepted notations:
+//!
+//! | Notation              | Example                    |
+//! |-----------------------|----------------------------|
+//! | `#rgb` / `#rgba`      | `"#f0a"`                   |
+//! | `#rrggbb`             | `"#ff00aa"`                |
+//! | `#rrggbbaa`           | `"#ff00aacc"`              |
+//! | `rgb()` / `rgba()`    | `"rgba(255, 0, 170, 0.8)"` |
+//! | CSS named color       | `"rebeccapurple"`          |
+//!
+//! Colors always serialize back to a normalized lowercase hex string —
+//! `#rrggbb` when fully opaque, `#rrggbbaa` otherwise — so templates receive
+//! the same shape of value regardless of how the theme spelled it.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// An sRGB color with an 8-bit alpha channel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Color {
+    /// Red channel.
+    pub r: u8,
+    /// Green channel.
+    pub g: u8,
+    /// Blue channel.
+    pub b: u8,
+    /// Alpha channel; `255` is fully opaque.
+    pub a: u8,
+}
+
+/// Error returned when a string cannot be parsed as a [`Color`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseColorError(String);
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ParseColorError {}
+
+impl Color {
+    /// Construct a fully opaque color from its red, green, and blue channels.
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b, a: 255 }
+    }
+
+    /// Construct a color from all four channels.
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Normalized lowercase hex representation (`#rrggbb` or `#rrggbbaa`).
+    pub fn to_hex(&self) -> String {
+        if self.a == 255 {
+            format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+        }
+    }
+
+    /// Hue (degrees, `0.0..360.0`), saturation, and lightness (both `0.0..=1.0`).
+    pub fn to_hsl(&self) -> (f32, f32, f32) {
+        let [r, g, b] = [self.r, self.g, self.b].map(|c| c as f32 / 255.0);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let l = (max + min) / 2.0;
+        let d = max - min;
+        if d == 0.0 {
+            return (0.0, 0.0, l);
+        }
+        let s = d / (1.0 - (2.0 * l - 1.0).abs());
+        let h = if max == r {
+            60.0 * ((g - b) / d).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / d + 2.0)
+        } else {
+            60.0 * ((r - g) / d + 4.0)
+        };
+        (h, s, l)
+    }
+
+    /// Construct a color from hue (degrees), saturation, lightness, and an alpha channel.
+    pub fn from_hsl(h: f32, s: f32, l: f32, a: u8) -> Self {
+        let s = s.clamp(0.0, 1.0);
+        let l = l.clamp(0.0, 1.0);
+        let h = h.rem_euclid(360.0);
+        let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
+        let m = l - c / 2.0;
+        let (r, g, b) = match h as u32 / 60 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+        let to_u8 = |v: f32| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+        Self::rgba(to_u8(r), to_u8(g), to_u8(b), a)
+    }
+
+    /// Increase HSL lightness by `amount` (`0.0..=1.0`).
+    pub fn lighten(&self, amount: f32) -> Self {
+        let (h, s, l) = self.to_hsl();
+        Self::from_hsl(h, s, l + amount, self.a)
+    }
+
+    /// Decrease HSL lightness by `amount` (`0.0..=1.0`).
+    pub fn darken(&self, amount: f32) -> Self {
+        self.lighten(-amount)
+    }
+
+    /// Increase HSL saturation by `amount` (`0.0..=1.0`).
+    pub fn saturate(&self, amount: f32) -> Self {
+        let (h, s, l) = self.to_hsl();
+        Self::from_hsl(h, s + amount, l, self.a)
+    }
+
+    /// Decrease HSL saturation by `amount` (`0.0..=1.0`).
+    pub fn desaturate(&self, amount: f32) -> Self {
+        self.saturate(-amount)
+    }
+
+    /// Blend towards `other`; `ratio` is the share of `other` in the result
+    /// (`0.0` returns `self`, `1.0` returns `other`).
+    pub fn mix(&self, other: &Color, ratio: f32) -> Self {
+        let t = ratio.clamp(0.0, 1.0);
+        let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        Self::rgba(
+            lerp(self.r, other.r),
+            lerp(self.g, other.g),
+            lerp(self.b, other.b),
+            lerp(self.a, other.a),
+        )
+    }
+
+    /// Replace the alpha channel with `alpha` (`0.0..=1.0`).
+    pub fn with_alpha(&self, alpha: f32) -> Self {
+        let a = (alpha.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Self::rgba(self.r, self.g, self.b, a)
+    }
+
+    /// Invert each color channel, keeping alpha.
+    pub fn invert(&self) -> Self {
+        Self::rgba(255 - self.r, 255 - self.g, 255 - self.b, self.a)
+    }
+
+    /// Rotate the hue by 180°.
+    pub fn complement(&self) -> Self {
+        let (h, s, l) = self.to_hsl();
+        Self::from_hsl(h + 180.0, s, l, self.a)
+    }
+
+    /// WCAG relative luminance (`0.0` for black, `1.0` for white), ignoring alpha.
+    pub fn luminance(&self) -> f32 {
+        let [r, g, b] = self.linear_rgb();
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+
+    /// WCAG contrast ratio against `other`, from `1.0` (identical) to `21.0`.
+    pub fn contrast(&self, other: &Color) -> f32 {
+        let (a, b) = (self.luminance(), other.luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    /// CIE L\*a\*b\* coordinates (D65 white point), ignoring alpha.
+    pub fn to_lab(&self) -> (f32, f32, f32) {
+        let [r, g, b] = [self.r, self.g, self.b].map(linear_channel);
+        let x = (0.4124564 * r + 0.3575761 * g + 0.1804375 * b) / 0.95047;
+        let y = 0.2126729 * r + 0.7151522 * g + 0.0721750 * b;
+        let z = (0.0193339 * r + 0.119192 * g + 0.9503041 * b) / 1.08883;
+        let f = |t: f32| match t > 216.0 / 24389.0 {
+            true => t.cbrt(),
+            false => (24389.0 / 27.0 * t + 16.0) / 116.0,
+        };
+        let (fx, fy, fz) = (f(x), f(y), f(z));
+        (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+    }
+
+    /// Perceptual distance to `other` (CIEDE2000 ΔE). About `2.3` is a just
+    /// noticeable difference; identical colors are `0.0`.
+    pub fn delta_e(&self, other: &Color) -> f32 {
+        ciede2000(self.to_lab(), other.to_lab())
+    }
+
+    /// Build a color from linear-light RGB channels in `0.0..=1.0`, keeping `a`.
+    pub(crate) fn from_linear(r: f32, g: f32, b: f32, a: u8) -> Self {
+        let encode = |c: f32| {
+            let c = c.clamp(0.0, 1.0);
+            let v = match c <= 0.0031308 {
+                true => c * 12.92,
+                false => 1.055 * c.powf(1.0 / 2.4) - 0.055,
+            };
+            (v * 255.0).round() as u8
+        };
+        Self::rgba(encode(r), encode(g), encode(b), a)
+    }
+
+    /// Linear-light RGB channels in `0.0..=1.0`.
+    pub(crate) fn linear_rgb(&self) -> [f32; 3] {
+        [self.r, self.g, self.b].map(linear_channel)
+    }
+
+    /// Nearest entry in the xterm 256-color palette, ignoring alpha.
+    ///
+    /// Only the 6×6×6 color cube (16–231) and the grayscale ramp (232–255) are
+    /// considered, since the first 16 entries vary between terminals.
+    pub fn to_xterm256(&self) -> u8 {
+        const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+        let nearest_level = |c: u8| {
+            (0..LEVELS.len())
+                .min_by_key(|&i| LEVELS[i].abs_diff(c))
+                .unwrap()
+        };
+        let distance = |o: Color| {
+            [(self.r, o.r), (self.g, o.g), (self.b, o.b)]
+                .iter()
+                .map(|&(a, b)| (a.abs_diff(b) as u32).pow(2))
+                .sum::<u32>()
+        };
+
+        let (r, g, b) = (
+            nearest_level(self.r),
+            nearest_level(self.g),
+            nearest_level(self.b),
+        );
+        let cube = Self::rgb(LEVELS[r], LEVELS[g], LEVELS[b]);
+        let cube_index = 16 + 36 * r + 6 * g + b;
+
+        let avg = (self.r as u32 + self.g as u32 + self.b as u32) / 3;
+        let step = (avg.saturating_sub(3) / 10).min(23);
+        let level = (8 + 10 * step) as u8;
+        let gray = Self::rgb(level, level, level);
+
+        match distance(gray) < distance(cube) {
+            true => 232 + step as u8,
+            false => cube_index as u8,
+        }
+    }
+
+    fn parse_hex(digits: &str) -> Option<Self> {
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        let nibble = |i: usize| u8::from_str_radix(&digits[i..=i], 16).ok().map(|n| n * 17);
+        let byte = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).ok();
+        match digits.len() {
+            3 => Some(Self::rgb(nibble(0)?, nibble(1)?, nibble(2)?)),
+            4 => Some(Self::rgba(nibble(0)?, nibble(1)?, nibble(2)?, nibble(3)?)),
+            6 => Some(Self::rgb(byte(0)?, byte(2)?, byte(4)?)),
+            8 => Some(Self::rgba(byte(0)?, byte(2)?, byte(4)?, byte(6)?)),
+            _ => None,
+        }
+    }
+
+    fn parse_functional(name: &str, args: &str) -> Option<Self> {
+        let parts: Vec<&str> = args
+            .split([',', ' ', '/'])
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .collect();
+        let channel = |s: &str| -> Option<u8> {
+            let v = match s.strip_suffix('%') {
+                Some(pct) => pct.parse::<f32>().ok()? / 100.0 * 255.0,
+                None => s.parse::<f32>().ok()?,
+            };
+            (0.0..=255.0).contains(&v).then(|| v.round() as u8)
+        };
+        let alpha = |s: &str| -> Option<u8> {
+            let v = match s.strip_suffix('%') {
+                Some(pct) => pct.parse::<f32>().ok()? / 100.0,
+                None => s.parse::<f32>().ok()?,
+            };
+            (0.0..=1.0).contains(&v).then(|| (v * 255.0).round() as u8)
+        };
+        match (name, parts.as_slice()) {
+            ("rgb" | "rgba", [r, g, b]) => Some(Self::rgb(channel(r)?, channel(g)?, channel(b)?)),
+            ("rgb" | "rgba", [r, g, b, a]) => {
+                Some(Self::rgba(channel(r)?, channel(g)?, channel(b)?, alpha(a)?))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Convert an sRGB channel to linear light.
+fn linear_channel(c: u8) -> f32 {
+    let c = c as f32 / 255.0;
+    match c <= 0.04045 {
+        true => c / 12.92,
+        false => ((c + 0.055) / 1.055).powf(2.4),
+    }
+}
+
+/// CIEDE2000 color difference between two L\*a\*b\* colors.
+fn ciede2000((l1, a1, b1): (f32, f32, f32), (l2, a2, b2): (f32, f32, f32)) -> f32 {
+    use std::f32::consts::PI;
+    let deg = |r: f32| r * 180.0 / PI;
+    let rad = |d: f32| d * PI / 180.0;
+
+    let c_bar = ((a1.hypot(b1)) + (a2.hypot(b2))) / 2.0;
+    let g = 0.5 * (1.0 - (c_bar.powi(7) / (c_bar.powi(7) + 25f32.powi(7))).sqrt());
+    let (a1, a2) = (a1 * (1.0 + g), a2 * (1.0 + g));
+    let (c1, c2) = (a1.hypot(b1), a2.hypot(b2));
+    let hue = |a: f32, b: f32| match a == 0.0 && b == 0.0 {
+        true => 0.0,
+        false => deg(b.atan2(a)).rem_euclid(360.0),
+    };
+    let (h1, h2) = (hue(a1, b1), hue(a2, b2));
+
+    let dl = l2 - l1;
+    let dc = c2 - c1;
+    let dh = match c1 * c2 == 0.0 {
+        true => 0.0,
+        false if (h2 - h1).abs() <= 180.0 => h2 - h1,
+        false if h2 - h1 > 180.0 => h2 - h1 - 360.0,
+        false => h2 - h1 + 360.0,
+    };
+    let dh = 2.0 * (c1 * c2).sqrt() * rad(dh / 2.0).sin();
+
+    let l_bar = (l1 + l2) / 2.0;
+    let c_bar = (c1 + c2) / 2.0;
+    let h_bar = match c1 * c2 == 0.0 {
+        true => h1 + h2,
+        false if (h1 - h2).abs() <= 180.0 => (h1 + h2) / 2.0,
+        false if h1 + h2 < 360.0 => (h1 + h2 + 360.0) / 2.0,
+        false => (h1 + h2 - 360.0) / 2.0,
+    };
+    let t = 1.0 - 0.17 * rad(h_bar - 30.0).cos()
+        + 0.24 * rad(2.0 * h_bar).cos()
+        + 0.32 * rad(3.0 * h_bar + 6.0).cos()
+        - 0.20 * rad(4.0 * h_bar - 63.0).cos();
+    let d_theta = 30.0 * (-((h_bar - 275.0) / 25.0).powi(2)).exp();
+    let r_c = 2.0 * (c_bar.powi(7) / (c_bar.powi(7) + 25f32.powi(7))).sqrt();
+    let s_l = 1.0 + 0.015 * (l_bar - 50.0).powi(2) / (20.0 + (l_bar - 50.0).powi(2)).sqrt();
+    let s_c = 1.0 + 0.045 * c_bar;
+    let s_h = 1.0 + 0.015 * c_bar * t;
+    let r_t = -rad(2.0 * d_theta).sin() * r_c;
+
+    ((dl / s_l).powi(2) + (dc / s_c).powi(2) + (dh / s_h).powi(2) + r_t * (dc / s_c) * (dh / s_h))
+        .sqrt()
+}
+
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let lower = trimmed.to_ascii_lowercase();
+
+        let parsed = if let Some(digits) = lower.strip_prefix('#') {
+            Self::parse_hex(digits)
+        } else if let Some((name, rest)) = lower.split_once('(') {
+            rest.strip_suffix(')')
+                .and_then(|args| Self::parse_functional(name.trim(), args))
+        } else {
+            named(&lower)
+        };
+
+        parsed.ok_or_else(|| {
+            ParseColorError(format!(
+                "invalid color '{trimmed}': expected #rgb, #rrggbb, #rrggbbaa, rgb(), rgba(), or a CSS color name"
+            ))
+        })
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Look up a CSS named color (case-insensitive input must already be lowercased).
+fn named(name: &str) -> Option<Color> {
+    if name == "transparent" {
+        return Some(Color::rgba(0, 0, 0, 0));
+    }
+    NAMED_COLORS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, hex)| Color::rgb((hex >> 16) as u8, (hex >> 8) as u8, *hex as u8))
+}
+
+/// CSS Color Module Level 4 named colors.
+const NAMED_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Color {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn contrast_ratios_match_wcag() {
+        let black = Color::rgb(0, 0, 0);
+        let white = Color::rgb(255, 255, 255);
+        assert!((black.contrast(&white) - 21.0).abs() < 0.01);
+        assert!((white.contrast(&black) - 21.0).abs() < 0.01);
+        assert!((parse("#777777").contrast(&white) - 4.48).abs() < 0.01);
+        assert_eq!(parse("#123456").contrast(&parse("#123456")), 1.0);
+    }
+
+    #[test]
+    fn ciede2000_matches_reference_data() {
+        // Pairs from Sharma, Wu & Dalal (2005), "The CIEDE2000 color-difference formula".
+        let cases = [
+            ((50.0, 2.6772, -79.7751), (50.0, 0.0, -82.7485), 2.0425),
+            ((50.0, -1.3802, -84.2814), (50.0, 0.0, -82.7485), 1.0),
+            ((50.0, 2.5, 0.0), (73.0, 25.0, -18.0), 27.1492),
+            (
+                (60.2574, -34.0099, 36.2677),
+                (60.4626, -34.1751, 39.4387),
+                1.2644,
+            ),
+        ];
+        for (lab1, lab2, expected) in cases {
+            let de = ciede2000(lab1, lab2);
+            assert!((de - expected).abs() < 1e-3, "{lab1:?} {lab2:?}: {de}");
+        }
+        assert_eq!(parse("#123456").delta_e(&parse("#123456")), 0.0);
+    }
+
+    #[test]
+    fn converts_to_lab() {
+        let (l, a, b) = parse("#ffffff").to_lab();
+        assert!((l - 100.0).abs() < 0.01 && a.abs() < 0.01 && b.abs() < 0.01);
+        let (l, a, b) = parse("#ff0000").to_lab();
+        assert!((l - 53.24).abs() < 0.05 && (a - 80.09).abs() < 0.1 && (b - 67.20).abs() < 0.1);
+    }
+
+    #[test]
+    fn quantizes_to_xterm256() {
+        assert_eq!(parse("#ff0000").to_xterm256(), 196);
+        assert_eq!(parse("#000000").to_xterm256(), 16);
+        assert_eq!(parse("#ffffff").to_xterm256(), 231);
+        assert_eq!(parse("#808080").to_xterm256(), 244);
+        assert_eq!(parse("#5f87af").to_xterm256(), 67);
+    }
+
+    #[test]
+    fn parses_hex_notations() {
+        assert_eq!(parse("#f0a"), Color::rgb(0xff, 0x00, 0xaa));
+        assert_eq!(parse("#FF00AA"), Color::rgb(0xff, 0x00, 0xaa));
+        assert_eq!(parse("#ff00aa80"), Color::rgba(0xff, 0x00, 0xaa, 0x80));
+    }
+
+    #[test]
+    fn parses_functional_notations() {
+        assert_eq!(parse("rgb(255, 0, 170)"), Color::rgb(255, 0, 170));
+        assert_eq!(
+            parse("rgba(255, 0, 170, 0.5)"),
+            Color::rgba(255, 0, 170, 128)
+        );
+        assert_eq!(parse("rgb(100%, 0%, 0%)"), Color::rgb(255, 0, 0));
+    }
+
+    #[test]
+    fn parses_named_colors() {
+        assert_eq!(parse("RebeccaPurple"), Color::rgb(0x66, 0x33, 0x99));
+        assert_eq!(parse("transparent").a, 0);
+    }
+
+    #[test]
+    fn rejects_malformed_values() {
+        for bad in [
+            "#ff1a3",
+            "#gggggg",
+            "rgb(256, 0, 0)",
+            "rgba(0, 0, 0, 2)",
+            "notacolor",
+            "",
+        ] {
+            assert!(bad.parse::<Color>().is_err(), "accepted '{bad}'");
+        }
+    }
+
+    #[test]
+    fn hsl_round_trip() {
+        for hex in ["#ff1a3e", "#00deff", "#080808", "#c0c0c0", "#663399"] {
+            let c = parse(hex);
+            let (h, s, l) = c.to_hsl();
+            assert_eq!(Color::from_hsl(h, s, l, 255), c, "{hex}");
+        }
+    }
+
+    #[test]
+    fn adjusts_lightness_and_saturation() {
+        assert_eq!(parse("#808080").lighten(0.5), parse("#ffffff"));
+        assert_eq!(parse("#808080").darken(1.0), parse("#000000"));
+        assert_eq!(parse("#bf4040").desaturate(1.0), parse("#808080"));
+        assert_eq!(parse("#ff0000").complement(), parse("#00ffff"));
+        assert_eq!(parse("#ff0000").invert(), parse("#00ffff"));
+    }
+
+    #[test]
+    fn mixes_and_sets_alpha() {
+        assert_eq!(
+            parse("#000000").mix(&parse("#ffffff"), 0.5),
+            parse("#808080")
+        );
+        assert_eq!(parse("#ff00aa").with_alpha(0.667).to_hex(), "#ff00aaaa");
+    }
+
+    #[test]
+    fn serializes_to_normalized_hex() {
+        assert_eq!(parse("RED").to_string(), "#ff0000");
+        assert_eq!(parse("rgba(0, 0, 0, 0)").to_string(), "#00000000");
+    }
+}
@@ -1,8 +1,8 @@
 //! Theme configuration types, mirroring the structure of a theme TOML file.
 //!
 //! Each struct corresponds to one TOML section. All fields are optional at the
-//! TOML level (represented as `Option<_>`) — the application is silently
-//! skipped if its top-level section is absent from the file.
+//! TOML level (represented as `Option<_>`) — the application is skipped if its
+//! top-level section is absent from the file, or sets `enabled = false`.
 //!
 //! | TOML section       | Struct                  | Tera root path      |
 //! |--------------------|-------------------------|---------------------|
@@ -12,8 +12,19 @@
 //! | `[polybar]`        | [`PolybarConfig`]       | `polybar.*`         |
 //! | `[alacritty]`      | [`AlacrittyConfig`]     | `alacritty.*`       |
 //! | `[picom]`          | [`PicomConfig`]         | `picom.*`           |
+//! | `[roles]`          | [`Theme::roles`]        | `roles.*`           |
+//! | `[variants.<name>]`| [`Theme::variants`]     | —                   |
+//! | any other table    | [`Theme::extra`]        | `<table>.*`         |
+//!
+//! Every struct also keeps keys it does not know about in a flattened `extra`
+//! map, so `[dunst]` sections or a `[polybar] radius` key reach templates
+//! exactly as written.
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use toml::Table;
+
+use super::color::Color;
 
 /// Top-level theme definition loaded from a TOML file.
 ///
@@ -31,10 +42,14 @@ use serde::{Deserialize, Serialize};
 /// [polybar]
 /// [alacritty]
 /// [picom]
+/// [roles]       # → roles.*
+///
+/// [variants.light.ansi.primary]   # selected with `axtc apply --variant light`
 /// ```
 ///
 /// Only `name` is required. Any section may be omitted; the corresponding
-/// application is silently skipped during `axtc apply`.
+/// application is skipped during `axtc apply`. Alacritty is also applied when
+/// only `[ansi]` is present.
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Theme {
     /// Unique theme name used to load it by key.
@@ -53,6 +68,22 @@ pub struct Theme {
     pub alacritty: Option<AlacrittyConfig>,
     /// picom compositor settings.
     pub picom: Option<PicomConfig>,
+    /// Semantic color roles set by the theme; see [`Theme::resolved_roles`]
+    /// for what templates receive.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub roles: BTreeMap<String, Color>,
+    /// Name of the variant this theme was loaded as (e.g. `light`), exposed to
+    /// templates as `variant`. A theme file may also set it to name its base
+    /// palette.
+    pub variant: Option<String>,
+    /// Named overrides merged over the rest of the theme when selected; see
+    /// [`Theme::with_variant`].
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variants: BTreeMap<String, Table>,
+    /// Unknown top-level keys and tables (e.g. `[rofi]`), passed through to
+    /// templates unchanged under their own name (`rofi.*`).
+    #[serde(flatten)]
+    pub extra: Table,
 }
 
 /// Settings shared across all app configs (e.g. font family, terminal).
@@ -76,6 +107,9 @@ pub struct GlobalConfig {
     /// Font family used as the default across alacritty, polybar, and herbstluftwm.
     /// Individual app configs override this when set.
     pub font: Option<String>,
+    /// Keys not listed above, passed through to templates unchanged.
+    #[serde(flatten)]
+    pub extra: Table,
 }
 
 /// ANSI terminal color palette, split into primary, normal, and bright groups.
@@ -119,59 +153,71 @@ pub struct AnsiConfig {
     pub normal: Option<NormalColors>,
     /// Bright variants of the 8 ANSI colors (indices 8–15).
     pub bright: Option<BrightColors>,
+    /// Keys not listed above, passed through to templates unchanged.
+    #[serde(flatten)]
+    pub extra: Table,
 }
 
 /// Background, foreground, and cursor colors for the terminal.
 #[derive(Debug, Deserialize, Serialize)]
 pub struct PrimaryColors {
     /// Default text color.
-    pub foreground: Option<String>,
+    pub foreground: Option<Color>,
     /// Terminal background color.
-    pub background: Option<String>,
+    pub background: Option<Color>,
     /// Cursor color.
-    pub cursor: Option<String>,
+    pub cursor: Option<Color>,
+    /// Keys not listed above, passed through to templates unchanged.
+    #[serde(flatten)]
+    pub extra: Table,
 }
 
-/// Standard 8 ANSI colors (indices 0–7). All values are hex color strings (e.g. `"#ff0000"`).
+/// Standard 8 ANSI colors (indices 0–7). All values are [`Color`]s (e.g. `"#ff0000"`).
 #[derive(Debug, Deserialize, Serialize)]
 pub struct NormalColors {
     /// Black (ANSI 0).
-    pub black: Option<String>,
+    pub black: Option<Color>,
     /// Red (ANSI 1).
-    pub red: Option<String>,
+    pub red: Option<Color>,
     /// Green (ANSI 2).
-    pub green: Option<String>,
+    pub green: Option<Color>,
     /// Yellow (ANSI 3).
-    pub yellow: Option<String>,
+    pub yellow: Option<Color>,
     /// Blue (ANSI 4).
-    pub blue: Option<String>,
+    pub blue: Option<Color>,
     /// Magenta (ANSI 5).
-    pub magenta: Option<String>,
+    pub magenta: Option<Color>,
     /// Cyan (ANSI 6).
-    pub cyan: Option<String>,
+    pub cyan: Option<Color>,
     /// White (ANSI 7).
-    pub white: Option<String>,
+    pub white: Option<Color>,
+    /// Keys not listed above, passed through to templates unchanged.
+    #[serde(flatten)]
+    pub extra: Table,
 }
 
-/// Bright variants of the 8 ANSI colors (indices 8–15). All values are hex color strings.
+/// Bright variants of the 8 ANSI colors (indices 8–15). All values are [`Color`]s.
 #[derive(Debug, Deserialize, Serialize)]
 pub struct BrightColors {
     /// Bright black (ANSI 8).
-    pub black: Option<String>,
+    pub black: Option<Color>,
     /// Bright red (ANSI 9).
-    pub red: Option<String>,
+    pub red: Option<Color>,
     /// Bright green (ANSI 10).
-    pub green: Option<String>,
+    pub green: Option<Color>,
     /// Bright yellow (ANSI 11).
-    pub yellow: Option<String>,
+    pub yellow: Option<Color>,
     /// Bright blue (ANSI 12).
-    pub blue: Option<String>,
+    pub blue: Option<Color>,
     /// Bright magenta (ANSI 13).
-    pub magenta: Option<String>,
+    pub magenta: Option<Color>,
     /// Bright cyan (ANSI 14).
-    pub cyan: Option<String>,
+    pub cyan: Option<Color>,
     /// Bright white (ANSI 15).
-    pub white: Option<String>,
+    pub white: Option<Color>,
+    /// Keys not listed above, passed through to templates unchanged.
+    #[serde(flatten)]
+    pub extra: Table,
 }
 
 /// herbstluftwm window manager configuration.
@@ -212,6 +258,9 @@ pub struct HerbstluftwmConfig {
     pub background_src: Option<String>,
     /// Terminal emulator spawned by the keybind. Defaults to `"alacritty"`.
     pub terminal: Option<String>,
+    /// Keys not listed above, passed through to templates unchanged.
+    #[serde(flatten)]
+    pub extra: Table,
 }
 
 /// polybar status-bar configuration.
@@ -252,12 +301,15 @@ pub struct PolybarConfig {
     /// Full polybar font string (e.g. `"firacode:size=12;2"`).
     /// Falls back to `global.font` and then a hardcoded default when absent.
     pub font: Option<String>,
-    /// Accent color used for module labels and icons (hex string).
+    /// Accent color used for module labels and icons.
     /// Defaults to `#ff8da1` when absent.
-    pub primary_color: Option<String>,
-    /// Background color for the active workspace label (hex string).
+    pub primary_color: Option<Color>,
+    /// Background color for the active workspace label.
     /// Defaults to `#2b1046` when absent.
-    pub background_alt: Option<String>,
+    pub background_alt: Option<Color>,
+    /// Keys not listed above, passed through to templates unchanged.
+    #[serde(flatten)]
+    pub extra: Table,
 }
 
 /// Alacritty terminal emulator configuration.
@@ -299,6 +351,9 @@ pub struct AlacrittyConfig {
     pub font_style: Option<String>,
     /// Window background opacity in the range `0.0`–`1.0`.
     pub opacity: Option<f32>,
+    /// Keys not listed above, passed through to templates unchanged.
+    #[serde(flatten)]
+    pub extra: Table,
 }
 
 /// picom compositor configuration.
@@ -336,12 +391,40 @@ pub struct PicomConfig {
     pub blur: Option<bool>,
     /// Window corner radius in pixels. `0` disables rounded corners.
     pub corner_radius: Option<u32>,
+    /// Keys not listed above, passed through to templates unchanged.
+    #[serde(flatten)]
+    pub extra: Table,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn unknown_sections_and_keys_are_kept() {
+        let toml = r##"
+name = "extra"
+
+[rofi]
+width = 600
+font  = "FiraCode 12"
+
+[polybar]
+height = 24
+radius = 6.0
+"##;
+        let theme: Theme = toml::from_str(toml).unwrap();
+        assert_eq!(theme.extra["rofi"]["width"].as_integer(), Some(600));
+        let polybar = theme.polybar.as_ref().unwrap();
+        assert_eq!(polybar.height, Some(24));
+        assert_eq!(polybar.extra["radius"].as_float(), Some(6.0));
+
+        let context = tera::Context::from_serialize(&theme).unwrap().into_json();
+        assert_eq!(context["rofi"]["font"], "FiraCode 12");
+        assert_eq!(context["polybar"]["radius"], 6.0);
+        assert_eq!(context["polybar"]["height"], 24);
+    }
+
     #[test]
     fn parse_minimal_theme() {
         let toml = r#"name = "test""#;
@@ -377,7 +460,7 @@ blur = false
         assert_eq!(theme.name, "full");
         let ansi = theme.ansi.unwrap();
         let primary = ansi.primary.unwrap();
-        assert_eq!(primary.foreground.unwrap(), "#e0e0e0");
+        assert_eq!(primary.foreground.unwrap().to_string(), "#e0e0e0");
         let hlwm = theme.herbstluftwm.unwrap();
         assert!(hlwm.borders.unwrap());
     }
@@ -398,4 +481,53 @@ opacity = 1.0
         let alacritty = reparsed.alacritty.unwrap();
         assert_eq!(alacritty.font_size.unwrap(), 12.0);
     }
+
+    #[test]
+    fn invalid_color_reports_key_and_line() {
+        let toml = r##"
+name = "bad"
+
+[ansi.normal]
+red = "#ff1a3"
+"##;
+        let err = Theme::from_toml_str(toml).unwrap_err().to_string();
+        assert!(err.starts_with("ansi.normal.red:"), "{err}");
+        assert!(err.contains("line 5"), "{err}");
+        assert!(err.contains("invalid color '#ff1a3'"), "{err}");
+    }
+
+    #[test]
+    fn invalid_color_with_references_reports_key_and_line() {
+        let toml = r##"
+name = "bad"
+
+[roles]
+accent = "${ansi.normal.magenta}"
+
+[ansi.normal]
+magenta = "#bd93f9"
+red     = "#ff1a3"
+"##;
+        let err = Theme::from_toml_str(toml).unwrap_err().to_string();
+        assert!(
+            err.starts_with("ansi.normal.red: line 9, column 11:"),
+            "{err}"
+        );
+        assert!(err.contains("invalid color '#ff1a3'"), "{err}");
+    }
+
+    #[test]
+    fn colors_are_normalized_on_serialize() {
+        let toml = r#"
+name = "normalized"
+
+[ansi.primary]
+background = "rgb(26, 26, 46)"
+foreground = "WhiteSmoke"
+"#;
+        let theme: Theme = toml::from_str(toml).unwrap();
+        let serialized = toml::to_string(&theme).unwrap();
+        assert!(serialized.contains(r##"background = "#1a1a2e""##));
+        assert!(serialized.contains(r##"foreground = "#f5f5f5""##));
+    }
 }
@@ -0,0 +1,207 @@
+//! Color-vision-deficiency simulation, used by `axtc check --cvd`.
+//!
+//! Colors are simulated with the full-severity matrices of Machado, Oliveira
+//! & Fernandes (2009), applied in linear RGB. Two ANSI colors are reported as
+//! confusable when their simulated CIEDE2000 distance falls below a threshold;
+//! only colors in the same group (normal or bright) are compared, since a
+//! normal color and its bright variant are meant to look alike.
+
+use anyhow::{Context, Result};
+
+use super::import::{ANSI_NAMES, Slots};
+use super::{Color, Theme};
+
+/// Default minimum CIEDE2000 distance between two ANSI colors.
+pub const DEFAULT_MIN_DISTANCE: f32 = 10.0;
+
+/// A type of dichromatic color vision.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum Deficiency {
+    /// No functioning long-wavelength (red) cones.
+    Protanopia,
+    /// No functioning medium-wavelength (green) cones.
+    Deuteranopia,
+    /// No functioning short-wavelength (blue) cones.
+    Tritanopia,
+}
+
+impl Deficiency {
+    fn matrix(&self) -> [[f32; 3]; 3] {
+        match self {
+            Self::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            Self::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+            Self::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ],
+        }
+    }
+
+    /// How `color` appears to someone with this deficiency. Alpha is kept.
+    pub fn simulate(&self, color: Color) -> Color {
+        let rgb = color.linear_rgb();
+        let [r, g, b] = self
+            .matrix()
+            .map(|row| row.iter().zip(rgb).map(|(m, c)| m * c).sum());
+        Color::from_linear(r, g, b, color.a)
+    }
+
+    /// A copy of `theme` with every color simulated, named `<name>-<deficiency>`.
+    ///
+    /// Colors in unknown sections are simulated too, as long as they are written
+    /// in hex or `rgb()` notation.
+    pub fn simulate_theme(&self, theme: &Theme) -> Result<Theme> {
+        let mut table = toml::Table::try_from(theme).context("could not serialize theme")?;
+        self.simulate_table(&mut table);
+        table.insert("name".into(), format!("{}-{self}", theme.name).into());
+        table.insert(
+            "description".into(),
+            format!("{} as seen with {self}", theme.name).into(),
+        );
+        Ok(toml::Value::Table(table).try_into()?)
+    }
+
+    fn simulate_table(&self, table: &mut toml::Table) {
+        for (_, value) in table.iter_mut() {
+            match value {
+                toml::Value::Table(t) => self.simulate_table(t),
+                toml::Value::String(s) if s.starts_with('#') || s.starts_with("rgb") => {
+                    if let Ok(c) = s.parse::<Color>() {
+                        *s = self.simulate(c).to_string();
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Two ANSI colors that are hard to tell apart with a deficiency.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfusablePair {
+    /// First color's path, e.g. `normal.red`.
+    pub first: String,
+    /// Second color's path, e.g. `normal.green`.
+    pub second: String,
+    /// CIEDE2000 distance with typical color vision.
+    pub distance: f32,
+    /// CIEDE2000 distance after simulation.
+    pub simulated_distance: f32,
+}
+
+/// ANSI color pairs of `theme` closer than `min_distance` under `deficiency`.
+pub fn confusable_pairs(
+    theme: &Theme,
+    deficiency: Deficiency,
+    min_distance: f32,
+) -> Vec<ConfusablePair> {
+    let slots = theme
+        .ansi
+        .as_ref()
+        .map(Slots::from_ansi)
+        .unwrap_or_default();
+    let mut pairs = vec![];
+    for (group, colors) in [
+        ("normal", &slots.indexed[..8]),
+        ("bright", &slots.indexed[8..]),
+    ] {
+        let set: Vec<(&str, Color)> = ANSI_NAMES
+            .iter()
+            .zip(colors)
+            .filter_map(|(name, c)| Some((*name, (*c)?)))
+            .collect();
+        for (i, (first, a)) in set.iter().enumerate() {
+            for (second, b) in &set[i + 1..] {
+                let simulated = deficiency.simulate(*a).delta_e(&deficiency.simulate(*b));
+                if simulated < min_distance {
+                    pairs.push(ConfusablePair {
+                        first: format!("{group}.{first}"),
+                        second: format!("{group}.{second}"),
+                        distance: a.delta_e(b),
+                        simulated_distance: simulated,
+                    });
+                }
+            }
+        }
+    }
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn theme() -> Theme {
+        Theme::from_toml_str(
+            r##"
+name = "cvd"
+
+[ansi.normal]
+red    = "#cc3333"
+green  = "#669900"
+blue   = "#3366ff"
+
+[polybar]
+primary_color = "#ff0000"
+
+[rofi]
+accent = "#00ff00"
+font   = "red"
+"##,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn grays_are_unchanged() {
+        for d in [
+            Deficiency::Protanopia,
+            Deficiency::Deuteranopia,
+            Deficiency::Tritanopia,
+        ] {
+            for hex in ["#000000", "#808080", "#ffffff"] {
+                let gray: Color = hex.parse().unwrap();
+                assert!(d.simulate(gray).delta_e(&gray) < 1.0, "{d} {hex}");
+            }
+        }
+    }
+
+    #[test]
+    fn red_and_green_collapse_for_deuteranopes() {
+        let pairs = confusable_pairs(&theme(), Deficiency::Deuteranopia, DEFAULT_MIN_DISTANCE);
+        assert_eq!(pairs.len(), 1, "{pairs:?}");
+        assert_eq!(
+            (pairs[0].first.as_str(), pairs[0].second.as_str()),
+            ("normal.red", "normal.green")
+        );
+        assert!(pairs[0].distance > DEFAULT_MIN_DISTANCE);
+
+        assert!(
+            confusable_pairs(&theme(), Deficiency::Tritanopia, DEFAULT_MIN_DISTANCE).is_empty()
+        );
+    }
+
+    #[test]
+    fn simulated_theme_maps_every_color() {
+        let d = Deficiency::Protanopia;
+        let sim = d.simulate_theme(&theme()).unwrap();
+        assert_eq!(sim.name, "cvd-protanopia");
+        let red: Color = "#cc3333".parse().unwrap();
+        assert_eq!(sim.ansi.unwrap().normal.unwrap().red, Some(d.simulate(red)));
+        let accent = sim.polybar.unwrap().primary_color.unwrap();
+        assert_eq!(accent, d.simulate("#ff0000".parse().unwrap()));
+        let rofi = sim.extra["rofi"].as_table().unwrap();
+        assert_ne!(rofi["accent"].as_str(), Some("#00ff00"));
+        assert_eq!(rofi["font"].as_str(), Some("red"));
+    }
+}
@@ -0,0 +1,345 @@
+//! Export a theme's `ansi` palette for tools axtc does not template, used by
+//! `axtc export`.
+//!
+//! Exports are built from the [`Theme`] alone, so they work without any
+//! templates installed. Slots the theme leaves unset are omitted, except for
+//! base16, which needs a complete palette:
+//!
+//! | Format             | Output                                              |
+//! |--------------------|-----------------------------------------------------|
+//! | `base16`           | base16 scheme YAML (`palette:` layout)              |
+//! | `xresources`       | `*.foreground`, `*.cursorColor`, `*.color0`–`15`    |
+//! | `kitty`            | kitty `foreground`, `cursor`, `color0`–`15` lines   |
+//! | `windows-terminal` | a Windows Terminal `schemes` entry (JSON)           |
+//! | `gpl`              | GIMP/Inkscape `.gpl` palette                        |
+//! | `css`              | `:root` custom properties (`--red`, `--bright-red`) |
+//!
+//! base16 has slots with no ANSI equivalent; they are derived from the
+//! palette: `base01`/`base02`/`base04`/`base06` are blends of background and
+//! foreground, `base09` (orange) blends red and yellow, and `base0F` is a
+//! darkened red.
+
+use anyhow::{Result, bail};
+
+use super::import::{ANSI_NAMES, Slots};
+use super::{Color, Theme};
+
+/// A palette export format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::Display, strum::EnumString, strum::EnumIter)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ExportFormat {
+    /// base16 scheme YAML.
+    Base16,
+    /// X resources.
+    Xresources,
+    /// kitty color config.
+    Kitty,
+    /// Windows Terminal color scheme JSON.
+    WindowsTerminal,
+    /// GIMP palette.
+    Gpl,
+    /// CSS custom properties.
+    Css,
+}
+
+/// Render the `ansi` palette of `theme` in `format`.
+pub fn export(theme: &Theme, format: ExportFormat) -> Result<String> {
+    let Some(ansi) = &theme.ansi else {
+        bail!("theme '{}' has no [ansi] colors to export", theme.name);
+    };
+    let slots = Slots::from_ansi(ansi);
+    Ok(match format {
+        ExportFormat::Base16 => base16(theme, &slots)?,
+        ExportFormat::Xresources => lines(&slots, |key, c| {
+            let key = match key {
+                "cursor" => "cursorColor".to_owned(),
+                k if k.parse::<u8>().is_ok() => format!("color{k}"),
+                k => k.to_owned(),
+            };
+            format!("*.{key}: {}", c.with_alpha(1.0))
+        }),
+        ExportFormat::Kitty => lines(&slots, |key, c| {
+            let c = c.with_alpha(1.0);
+            match key.parse::<u8>() {
+                Ok(_) => format!("color{key} {c}"),
+                Err(_) => format!("{key} {c}"),
+            }
+        }),
+        ExportFormat::WindowsTerminal => windows_terminal(theme, &slots)?,
+        ExportFormat::Gpl => gpl(theme, &slots),
+        ExportFormat::Css => css(&slots),
+    })
+}
+
+/// Every set slot as `(key, color)`, where `key` is `foreground`, `background`,
+/// `cursor`, or an index `0`–`15`.
+fn entries(slots: &Slots) -> Vec<(String, Color)> {
+    let primary = [
+        ("foreground", slots.foreground),
+        ("background", slots.background),
+        ("cursor", slots.cursor),
+    ]
+    .into_iter()
+    .filter_map(|(k, c)| Some((k.to_owned(), c?)));
+    let indexed = slots
+        .indexed
+        .iter()
+        .enumerate()
+        .filter_map(|(i, c)| Some((i.to_string(), (*c)?)));
+    primary.chain(indexed).collect()
+}
+
+/// Name of the indexed slot `i` (`red`, `bright-red`, …) joined with `sep`.
+fn slot_name(i: usize, sep: &str) -> String {
+    match i < 8 {
+        true => ANSI_NAMES[i].to_owned(),
+        false => format!("bright{sep}{}", ANSI_NAMES[i - 8]),
+    }
+}
+
+fn lines(slots: &Slots, line: impl Fn(&str, Color) -> String) -> String {
+    entries(slots)
+        .into_iter()
+        .map(|(key, c)| line(&key, c) + "\n")
+        .collect()
+}
+
+fn base16(theme: &Theme, slots: &Slots) -> Result<String> {
+    let missing: Vec<String> = slots
+        .missing()
+        .into_iter()
+        .filter(|m| m != "primary.cursor")
+        .collect();
+    if !missing.is_empty() {
+        bail!(
+            "base16 needs a complete palette; theme '{}' does not set {}",
+            theme.name,
+            missing.join(", ")
+        );
+    }
+    let (bg, fg) = (slots.background.unwrap(), slots.foreground.unwrap());
+    let ansi = |i: usize| slots.indexed[i].unwrap();
+    let palette = [
+        bg,
+        bg.mix(&fg, 0.1),
+        bg.mix(&fg, 0.2),
+        ansi(8),
+        bg.mix(&fg, 0.7),
+        fg,
+        fg.mix(&ansi(15), 0.5),
+        ansi(15),
+        ansi(1),
+        ansi(1).mix(&ansi(3), 0.5),
+        ansi(3),
+        ansi(2),
+        ansi(6),
+        ansi(4),
+        ansi(5),
+        ansi(1).darken(0.15),
+    ];
+
+    let variant = match bg.luminance() < 0.5 {
+        true => "dark",
+        false => "light",
+    };
+    let mut out = format!(
+        "system: \"base16\"\nname: {}\nauthor: \"axtc\"\nvariant: \"{variant}\"\npalette:\n",
+        serde_json::to_string(&theme.name)?
+    );
+    for (i, color) in palette.iter().enumerate() {
+        out += &format!("  base{i:02X}: \"{}\"\n", color.with_alpha(1.0));
+    }
+    Ok(out)
+}
+
+/// ANSI color names as Windows Terminal spells them (magenta is "purple").
+const WINDOWS_TERMINAL_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "purple", "cyan", "white",
+];
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .map(|c| c.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+fn windows_terminal(theme: &Theme, slots: &Slots) -> Result<String> {
+    let mut scheme = serde_json::Map::new();
+    scheme.insert("name".into(), theme.name.clone().into());
+    for (key, color) in entries(slots) {
+        let key = match key.parse::<usize>() {
+            Ok(i) if i < 8 => WINDOWS_TERMINAL_NAMES[i].to_owned(),
+            Ok(i) => format!("bright{}", capitalize(WINDOWS_TERMINAL_NAMES[i - 8])),
+            Err(_) if key == "cursor" => "cursorColor".to_owned(),
+            Err(_) => key,
+        };
+        scheme.insert(key, color.with_alpha(1.0).to_string().into());
+    }
+    Ok(serde_json::to_string_pretty(&scheme)? + "\n")
+}
+
+fn gpl(theme: &Theme, slots: &Slots) -> String {
+    let mut out = format!("GIMP Palette\nName: {}\nColumns: 8\n#\n", theme.name);
+    for (key, c) in entries(slots) {
+        let name = match key.parse::<usize>() {
+            Ok(i) => slot_name(i, " "),
+            Err(_) => key,
+        };
+        out += &format!("{:3} {:3} {:3}\t{name}\n", c.r, c.g, c.b);
+    }
+    out
+}
+
+fn css(slots: &Slots) -> String {
+    let mut out = String::from(":root {\n");
+    for (key, c) in entries(slots) {
+        let name = match key.parse::<usize>() {
+            Ok(i) => slot_name(i, "-"),
+            Err(_) => key,
+        };
+        out += &format!("  --{name}: {c};\n");
+    }
+    out + "}\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FULL: &str = r##"
+name = "export"
+
+[ansi.primary]
+background = "#101010"
+foreground = "#e0e0e0"
+cursor     = "#ff8800"
+
+[ansi.normal]
+black   = "#000000"
+red     = "#cc0000"
+green   = "#00cc00"
+yellow  = "#cccc00"
+blue    = "#0000cc"
+magenta = "#cc00cc"
+cyan    = "#00cccc"
+white   = "#cccccc"
+
+[ansi.bright]
+black   = "#555555"
+red     = "#ff0000"
+green   = "#00ff00"
+yellow  = "#ffff00"
+blue    = "#0000ff"
+magenta = "#ff00ff"
+cyan    = "#00ffff"
+white   = "#ffffff"
+"##;
+
+    fn export_full(format: ExportFormat) -> String {
+        export(&Theme::from_toml_str(FULL).unwrap(), format).unwrap()
+    }
+
+    #[test]
+    fn base16_round_trips_through_import() {
+        let yaml = export_full(ExportFormat::Base16);
+        assert!(yaml.contains("variant: \"dark\""));
+        assert!(yaml.contains("  base0D: \"#0000cc\""));
+        let theme = crate::theme::import::base16::from_yaml_str("again", &yaml).unwrap();
+        let ansi = theme.ansi.unwrap();
+        assert_eq!(ansi.normal.unwrap().red.unwrap().to_string(), "#cc0000");
+        assert_eq!(ansi.bright.unwrap().black.unwrap().to_string(), "#555555");
+    }
+
+    #[test]
+    fn base16_needs_complete_palette() {
+        let theme =
+            Theme::from_toml_str("name = \"t\"\n[ansi.normal]\nred = \"#ff0000\"\n").unwrap();
+        let err = export(&theme, ExportFormat::Base16)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("does not set primary.foreground, primary.background, normal.black"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn xresources_and_kitty_lines() {
+        let xr = export_full(ExportFormat::Xresources);
+        assert!(
+            xr.starts_with(
+                "*.foreground: #e0e0e0\n*.background: #101010\n*.cursorColor: #ff8800\n"
+            )
+        );
+        assert!(xr.contains("*.color9: #ff0000\n"));
+        let kitty = export_full(ExportFormat::Kitty);
+        assert!(kitty.contains("cursor #ff8800\n"));
+        assert!(kitty.ends_with("color15 #ffffff\n"));
+    }
+
+    #[test]
+    fn xresources_and_kitty_drop_alpha() {
+        let theme = Theme::from_toml_str(
+            "name = \"t\"\n[ansi.primary]\nbackground = \"rgba(16, 16, 16, 0.8)\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            export(&theme, ExportFormat::Xresources).unwrap(),
+            "*.background: #101010\n"
+        );
+        assert_eq!(
+            export(&theme, ExportFormat::Kitty).unwrap(),
+            "background #101010\n"
+        );
+    }
+
+    #[test]
+    fn windows_terminal_scheme() {
+        let json: serde_json::Value =
+            serde_json::from_str(&export_full(ExportFormat::WindowsTerminal)).unwrap();
+        assert_eq!(json["name"], "export");
+        assert_eq!(json["cursorColor"], "#ff8800");
+        assert_eq!(json["purple"], "#cc00cc");
+        assert_eq!(json["brightPurple"], "#ff00ff");
+        assert_eq!(json["brightBlack"], "#555555");
+    }
+
+    #[test]
+    fn gpl_and_css() {
+        let gpl = export_full(ExportFormat::Gpl);
+        assert!(gpl.starts_with("GIMP Palette\nName: export\n"));
+        assert!(gpl.contains("255   0 255\tbright magenta\n"));
+        let css = export_full(ExportFormat::Css);
+        assert!(css.contains("  --bright-red: #ff0000;\n"));
+        assert!(css.ends_with("}\n"));
+    }
+
+    #[test]
+    fn unset_slots_are_omitted() {
+        let theme =
+            Theme::from_toml_str("name = \"t\"\n[ansi.normal]\nred = \"#ff0000\"\n").unwrap();
+        assert_eq!(
+            export(&theme, ExportFormat::Kitty).unwrap(),
+            "color1 #ff0000\n"
+        );
+        let err = export(
+            &Theme::from_toml_str("name = \"t\"").unwrap(),
+            ExportFormat::Css,
+        );
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "theme 't' has no [ansi] colors to export"
+        );
+    }
+
+    #[test]
+    fn formats_parse_from_kebab_case() {
+        assert_eq!(
+            "windows-terminal".parse::<ExportFormat>().unwrap(),
+            ExportFormat::WindowsTerminal
+        );
+        assert_eq!(ExportFormat::Gpl.to_string(), "gpl");
+    }
+}
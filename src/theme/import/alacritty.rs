@@ -0,0 +1,112 @@
+//! Alacritty `[colors.*]` tables from `alacritty.toml`.
+//!
+//! Reads `colors.primary.{foreground,background}`, `colors.cursor.cursor`,
+//! and the eight colors of `colors.normal` and `colors.bright`. Colors may be
+//! written as `#rrggbb` or `0xrrggbb`. A cursor set to `CellForeground` or
+//! `CellBackground`, which follows the cell under it, is left unset and
+//! reported as missing.
+
+use anyhow::{Context, Result};
+use std::path::Path;
+use toml::Table;
+
+use super::{ANSI_NAMES, Imported, Slots, load_with, parse_color};
+
+/// Read the `[colors]` tables of the Alacritty config at `path` into a theme called `name`.
+pub fn load(name: &str, path: &Path) -> Result<Imported> {
+    load_with(path, "alacritty", |content| from_toml_str(name, content))
+}
+
+/// Convert Alacritty config TOML into a theme called `name`.
+pub fn from_toml_str(name: &str, content: &str) -> Result<Imported> {
+    let config: Table = toml::from_str(content)?;
+    let colors = config
+        .get("colors")
+        .and_then(|c| c.as_table())
+        .context("config has no [colors] section")?;
+
+    let mut slots = Slots::default();
+    let mut read = |table: &str, key: &str, slot: &str| -> Result<()> {
+        let value = colors
+            .get(table)
+            .and_then(|t| t.get(key))
+            .and_then(|v| v.as_str());
+        if let Some(value) = value.filter(|v| !matches!(*v, "CellForeground" | "CellBackground")) {
+            slots.set(slot, parse_color(&format!("colors.{table}.{key}"), value)?);
+        }
+        Ok(())
+    };
+    read("primary", "foreground", "foreground")?;
+    read("primary", "background", "background")?;
+    read("cursor", "cursor", "cursor")?;
+    for (i, color) in ANSI_NAMES.iter().enumerate() {
+        read("normal", color, &i.to_string())?;
+        read("bright", color, &(i + 8).to_string())?;
+    }
+    Ok(slots.into_imported(name, None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_color_tables() {
+        let imported = from_toml_str(
+            "alac",
+            r##"
+[font]
+size = 12
+
+[colors.primary]
+background = "0x1d1f21"
+foreground = "#c5c8c6"
+
+[colors.cursor]
+text   = "#1d1f21"
+cursor = "#ffffff"
+
+[colors.normal]
+black = "#282a2e"
+red   = "#a54242"
+
+[colors.bright]
+red = "#cc6666"
+"##,
+        )
+        .unwrap();
+        let ansi = imported.theme.ansi.as_ref().unwrap();
+        let primary = ansi.primary.as_ref().unwrap();
+        assert_eq!(primary.background.unwrap().to_string(), "#1d1f21");
+        assert_eq!(primary.cursor.unwrap().to_string(), "#ffffff");
+        assert_eq!(
+            ansi.normal.as_ref().unwrap().red.unwrap().to_string(),
+            "#a54242"
+        );
+        assert_eq!(
+            ansi.bright.as_ref().unwrap().red.unwrap().to_string(),
+            "#cc6666"
+        );
+        assert!(imported.missing.contains(&"normal.green".to_owned()));
+        assert!(imported.missing.contains(&"bright.black".to_owned()));
+        assert!(!imported.missing.contains(&"primary.cursor".to_owned()));
+    }
+
+    #[test]
+    fn cell_colors_are_reported_as_missing() {
+        let imported = from_toml_str(
+            "t",
+            "[colors.cursor]\ncursor = \"CellForeground\"\ntext = \"CellBackground\"\n",
+        )
+        .unwrap();
+        assert!(imported.missing.contains(&"primary.cursor".to_owned()));
+    }
+
+    #[test]
+    fn invalid_colors_name_their_key() {
+        let err = from_toml_str("t", "[colors.normal]\nred = \"nope\"\n")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(err, "invalid color for 'colors.normal.red': 'nope'");
+    }
+}
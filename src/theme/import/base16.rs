@@ -0,0 +1,216 @@
+//! [base16] and [base24] scheme YAML.
+//!
+//! Both the original flat layout (`scheme:`, `base00:` … at the top level)
+//! and the newer layout with a `palette:` map are accepted. Colors map onto
+//! the theme with the standard base16 terminal mapping:
+//!
+//! | Theme slot                 | base16         | base24 override |
+//! |----------------------------|----------------|-----------------|
+//! | background                 | `base00`       |                 |
+//! | foreground, cursor         | `base05`       |                 |
+//! | black / bright black       | `base00` / `base03` |            |
+//! | red                        | `base08`       | bright: `base12` |
+//! | yellow                     | `base0A`       | bright: `base13` |
+//! | green                      | `base0B`       | bright: `base14` |
+//! | cyan                       | `base0C`       | bright: `base15` |
+//! | blue                       | `base0D`       | bright: `base16` |
+//! | magenta                    | `base0E`       | bright: `base17` |
+//! | white / bright white       | `base05` / `base07` |            |
+//! | `polybar.primary_color`    | `base0D`       |                 |
+//! | `polybar.background_alt`   | `base02`       |                 |
+//!
+//! [base16]: https://github.com/tinted-theming/home
+//! [base24]: https://github.com/tinted-theming/base24
+
+use anyhow::{Result, bail};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::{load_with, palette_theme, parse_color};
+use crate::theme::{
+    AnsiConfig, BrightColors, Color, NormalColors, PolybarConfig, PrimaryColors, Theme,
+};
+
+#[derive(Deserialize)]
+struct Scheme {
+    #[serde(alias = "scheme")]
+    name: Option<String>,
+    author: Option<String>,
+    palette: Option<BTreeMap<String, String>>,
+    #[serde(flatten)]
+    rest: BTreeMap<String, serde_yaml::Value>,
+}
+
+/// Read the scheme at `path` into a theme called `name`.
+pub fn load(name: &str, path: &Path) -> Result<Theme> {
+    load_with(path, "base16", |content| from_yaml_str(name, content))
+}
+
+/// Convert base16 or base24 scheme YAML into a theme called `name`.
+pub fn from_yaml_str(name: &str, content: &str) -> Result<Theme> {
+    let scheme: Scheme = serde_yaml::from_str(content)?;
+    let colors: BTreeMap<String, String> = match scheme.palette {
+        Some(palette) => palette,
+        None => scheme
+            .rest
+            .into_iter()
+            .filter_map(|(k, v)| Some((k, v.as_str()?.to_owned())))
+            .collect(),
+    };
+    let base = |slot: &str| -> Result<Color> {
+        let key = format!("base{slot}");
+        let value = colors
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(&key))
+            .map(|(_, v)| v);
+        match value {
+            Some(v) => parse_color(&key, v),
+            None => bail!("scheme has no '{key}' color"),
+        }
+    };
+    let base24 = |slot: &str, fallback: Color| -> Result<Color> {
+        match colors
+            .keys()
+            .any(|k| k.eq_ignore_ascii_case(&format!("base{slot}")))
+        {
+            true => base(slot),
+            false => Ok(fallback),
+        }
+    };
+
+    let (red, yellow, green) = (base("08")?, base("0A")?, base("0B")?);
+    let (cyan, blue, magenta) = (base("0C")?, base("0D")?, base("0E")?);
+    let ansi = AnsiConfig {
+        primary: Some(PrimaryColors {
+            foreground: Some(base("05")?),
+            background: Some(base("00")?),
+            cursor: Some(base("05")?),
+            extra: Default::default(),
+        }),
+        normal: Some(NormalColors {
+            black: Some(base("00")?),
+            red: Some(red),
+            green: Some(green),
+            yellow: Some(yellow),
+            blue: Some(blue),
+            magenta: Some(magenta),
+            cyan: Some(cyan),
+            white: Some(base("05")?),
+            extra: Default::default(),
+        }),
+        bright: Some(BrightColors {
+            black: Some(base("03")?),
+            red: Some(base24("12", red)?),
+            green: Some(base24("14", green)?),
+            yellow: Some(base24("13", yellow)?),
+            blue: Some(base24("16", blue)?),
+            magenta: Some(base24("17", magenta)?),
+            cyan: Some(base24("15", cyan)?),
+            white: Some(base("07")?),
+            extra: Default::default(),
+        }),
+        extra: Default::default(),
+    };
+
+    let description = match (scheme.name, scheme.author) {
+        (Some(n), Some(a)) => Some(format!("{n} by {a}")),
+        (Some(n), None) => Some(n),
+        _ => None,
+    };
+    let mut theme = palette_theme(name, description, ansi);
+    theme.polybar = Some(PolybarConfig {
+        position: None,
+        height: None,
+        font: None,
+        primary_color: Some(blue),
+        background_alt: Some(base("02")?),
+        extra: Default::default(),
+    });
+    Ok(theme)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FLAT: &str = r#"
+scheme: "Default Dark"
+author: "Chris Kempson (http://chriskempson.com)"
+base00: "181818"
+base01: "282828"
+base02: "383838"
+base03: "585858"
+base04: "b8b8b8"
+base05: "d8d8d8"
+base06: "e8e8e8"
+base07: "f8f8f8"
+base08: "ab4642"
+base09: "dc9656"
+base0A: "f7ca88"
+base0B: "a1b56c"
+base0C: "86c1b9"
+base0D: "7cafc2"
+base0E: "ba8baf"
+base0F: "a16946"
+"#;
+
+    fn hex(c: Option<Color>) -> String {
+        c.unwrap().to_string()
+    }
+
+    #[test]
+    fn flat_scheme_uses_terminal_mapping() {
+        let theme = from_yaml_str("default-dark", FLAT).unwrap();
+        assert_eq!(
+            theme.description.as_deref(),
+            Some("Default Dark by Chris Kempson (http://chriskempson.com)")
+        );
+        let ansi = theme.ansi.unwrap();
+        let (primary, normal, bright) = (
+            ansi.primary.unwrap(),
+            ansi.normal.unwrap(),
+            ansi.bright.unwrap(),
+        );
+        assert_eq!(hex(primary.background), "#181818");
+        assert_eq!(hex(primary.foreground), "#d8d8d8");
+        assert_eq!(hex(normal.red), "#ab4642");
+        assert_eq!(hex(normal.yellow), "#f7ca88");
+        assert_eq!(hex(normal.magenta), "#ba8baf");
+        assert_eq!(hex(bright.black), "#585858");
+        assert_eq!(hex(bright.red), "#ab4642");
+        assert_eq!(hex(bright.white), "#f8f8f8");
+        let polybar = theme.polybar.unwrap();
+        assert_eq!(hex(polybar.primary_color), "#7cafc2");
+        assert_eq!(hex(polybar.background_alt), "#383838");
+    }
+
+    #[test]
+    fn base24_palette_overrides_brights() {
+        let mut yaml = String::from("system: \"base24\"\nname: \"Test\"\npalette:\n");
+        for line in FLAT.lines().filter(|l| l.starts_with("base")) {
+            let (key, value) = line.split_once(": ").unwrap();
+            yaml += &format!("  {key}: \"#{}\"\n", value.trim_matches('"'));
+        }
+        yaml += "  base12: \"#ff0000\"\n  base17: \"#ff00ff\"\n";
+
+        let theme = from_yaml_str("t", &yaml).unwrap();
+        assert_eq!(theme.description.as_deref(), Some("Test"));
+        let bright = theme.ansi.unwrap().bright.unwrap();
+        assert_eq!(hex(bright.red), "#ff0000");
+        assert_eq!(hex(bright.magenta), "#ff00ff");
+        assert_eq!(hex(bright.green), "#a1b56c");
+    }
+
+    #[test]
+    fn missing_and_invalid_colors_are_reported() {
+        let err = from_yaml_str("t", "scheme: x\nbase00: \"000000\"\n")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(err, "scheme has no 'base08' color");
+
+        let broken = FLAT.replace("ab4642", "zz4642");
+        let err = from_yaml_str("t", &broken).unwrap_err().to_string();
+        assert_eq!(err, "invalid color for 'base08': 'zz4642'");
+    }
+}
@@ -0,0 +1,92 @@
+//! Kitty `kitty.conf` color lines.
+//!
+//! Reads `foreground`, `background`, `cursor`, and `color0`–`color15`.
+//! Every other line, including comments and `include` directives, is ignored.
+//! A slot set to `none` (e.g. `cursor none`, which follows the text color) is
+//! left unset and reported as missing.
+
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use super::{Imported, Slots, load_with, parse_color};
+
+/// Read the color lines of the kitty config at `path` into a theme called `name`.
+pub fn load(name: &str, path: &Path) -> Result<Imported> {
+    load_with(path, "kitty", |content| from_conf_str(name, content))
+}
+
+/// Convert kitty config source into a theme called `name`.
+pub fn from_conf_str(name: &str, content: &str) -> Result<Imported> {
+    let mut slots = Slots::default();
+    for (n, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once(char::is_whitespace) else {
+            continue;
+        };
+        let slot = key.strip_prefix("color").unwrap_or(key);
+        if !matches!(slot, "foreground" | "background" | "cursor") && slot.parse::<usize>().is_err()
+        {
+            continue;
+        }
+        if value.trim() == "none" {
+            continue;
+        }
+        let color = parse_color(key, value).with_context(|| format!("line {}", n + 1))?;
+        slots.set(slot, color);
+    }
+    Ok(slots.into_imported(name, None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_color_lines() {
+        let imported = from_conf_str(
+            "kit",
+            "# theme\nfont_size 11.0\nforeground   #dddddd\nbackground #000000\n\
+             cursor #cccccc\ncolor1 #cc0403\ncolor9\t#f2201f\ncolor16 #ffffff\n\
+             selection_background #333333\n",
+        )
+        .unwrap();
+        let ansi = imported.theme.ansi.as_ref().unwrap();
+        assert_eq!(
+            ansi.primary
+                .as_ref()
+                .unwrap()
+                .foreground
+                .unwrap()
+                .to_string(),
+            "#dddddd"
+        );
+        assert_eq!(
+            ansi.normal.as_ref().unwrap().red.unwrap().to_string(),
+            "#cc0403"
+        );
+        assert_eq!(
+            ansi.bright.as_ref().unwrap().red.unwrap().to_string(),
+            "#f2201f"
+        );
+        assert_eq!(imported.missing.len(), 14);
+    }
+
+    #[test]
+    fn none_is_reported_as_missing() {
+        let imported = from_conf_str("t", "cursor none\ncolor1 #cc0403\n").unwrap();
+        assert!(imported.missing.contains(&"primary.cursor".to_owned()));
+        assert!(!imported.missing.contains(&"normal.red".to_owned()));
+    }
+
+    #[test]
+    fn invalid_colors_name_their_line() {
+        let err = from_conf_str("t", "color0 #000000\ncolor1 nope\n").unwrap_err();
+        assert!(
+            format!("{err:#}").starts_with("line 2: invalid color for 'color1': 'nope'"),
+            "{err:#}"
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! Import color schemes from other tools as axtc themes, used by `axtc import`.
+//!
+//! Each submodule reads one format and returns a [`Theme`] holding the
+//! scheme's colors; every other section is left unset. Formats that may
+//! leave slots out report them in [`Imported::missing`].
+
+pub mod alacritty;
+pub mod base16;
+pub mod kitty;
+pub mod xresources;
+
+use anyhow::{Context, Result};
+
+use super::{AnsiConfig, BrightColors, Color, NormalColors, PrimaryColors, Theme};
+
+/// ANSI color names in index order (0–7, and 8–15 for their bright variants).
+pub const ANSI_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+/// A theme read from another tool's scheme.
+#[derive(Debug)]
+pub struct Imported {
+    /// The theme, with `ansi` filled from the scheme.
+    pub theme: Theme,
+    /// Dotted `ansi` paths the scheme did not set (e.g. `normal.red`).
+    pub missing: Vec<String>,
+}
+
+/// Colors collected from a scheme, by terminal slot.
+///
+/// Also used by [`export`](crate::theme::export) to read a theme's palette.
+#[derive(Debug, Default)]
+pub(crate) struct Slots {
+    pub(crate) foreground: Option<Color>,
+    pub(crate) background: Option<Color>,
+    pub(crate) cursor: Option<Color>,
+    /// Indices 0–15: the normal colors followed by the bright ones.
+    pub(crate) indexed: [Option<Color>; 16],
+}
+
+impl Slots {
+    /// The palette of a theme's `ansi` section.
+    pub(crate) fn from_ansi(ansi: &AnsiConfig) -> Self {
+        let mut slots = Self::default();
+        if let Some(p) = &ansi.primary {
+            (slots.foreground, slots.background, slots.cursor) =
+                (p.foreground, p.background, p.cursor);
+        }
+        if let Some(n) = &ansi.normal {
+            slots.indexed[..8].copy_from_slice(&[
+                n.black, n.red, n.green, n.yellow, n.blue, n.magenta, n.cyan, n.white,
+            ]);
+        }
+        if let Some(b) = &ansi.bright {
+            slots.indexed[8..].copy_from_slice(&[
+                b.black, b.red, b.green, b.yellow, b.blue, b.magenta, b.cyan, b.white,
+            ]);
+        }
+        slots
+    }
+
+    /// Set the color for `key`, which is `foreground`, `background`, `cursor`, or
+    /// an index `0`–`15`. Returns whether the key names a slot.
+    fn set(&mut self, key: &str, color: Color) -> bool {
+        let slot = match key {
+            "foreground" => &mut self.foreground,
+            "background" => &mut self.background,
+            "cursor" => &mut self.cursor,
+            _ => match key.parse::<usize>() {
+                Ok(i) if i < 16 => &mut self.indexed[i],
+                _ => return false,
+            },
+        };
+        *slot = Some(color);
+        true
+    }
+
+    pub(crate) fn missing(&self) -> Vec<String> {
+        let primary = [
+            ("foreground", self.foreground),
+            ("background", self.background),
+            ("cursor", self.cursor),
+        ]
+        .into_iter()
+        .filter(|(_, c)| c.is_none())
+        .map(|(name, _)| format!("primary.{name}"));
+        let indexed = self
+            .indexed
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.is_none())
+            .map(|(i, _)| match i < 8 {
+                true => format!("normal.{}", ANSI_NAMES[i]),
+                false => format!("bright.{}", ANSI_NAMES[i - 8]),
+            });
+        primary.chain(indexed).collect()
+    }
+
+    fn into_imported(self, name: &str, description: Option<String>) -> Imported {
+        let missing = self.missing();
+        // Groups the scheme leaves out entirely are omitted rather than written as empty tables.
+        let [n, b] = [0, 8].map(|offset| self.indexed[offset..offset + 8].to_vec());
+        let ansi = AnsiConfig {
+            primary: Some(PrimaryColors {
+                foreground: self.foreground,
+                background: self.background,
+                cursor: self.cursor,
+                extra: Default::default(),
+            }),
+            normal: n.iter().any(Option::is_some).then(|| NormalColors {
+                black: n[0],
+                red: n[1],
+                green: n[2],
+                yellow: n[3],
+                blue: n[4],
+                magenta: n[5],
+                cyan: n[6],
+                white: n[7],
+                extra: Default::default(),
+            }),
+            bright: b.iter().any(Option::is_some).then(|| BrightColors {
+                black: b[0],
+                red: b[1],
+                green: b[2],
+                yellow: b[3],
+                blue: b[4],
+                magenta: b[5],
+                cyan: b[6],
+                white: b[7],
+                extra: Default::default(),
+            }),
+            extra: Default::default(),
+        };
+        Imported {
+            theme: palette_theme(name, description, ansi),
+            missing,
+        }
+    }
+}
+
+/// A theme with only `ansi` (and an optional description) set.
+fn palette_theme(name: &str, description: Option<String>, ansi: AnsiConfig) -> Theme {
+    Theme {
+        name: name.to_owned(),
+        description,
+        global: None,
+        ansi: Some(ansi),
+        herbstluftwm: None,
+        polybar: None,
+        alacritty: None,
+        picom: None,
+        roles: Default::default(),
+        variant: None,
+        variants: Default::default(),
+        extra: Default::default(),
+    }
+}
+
+/// Parse a color written with a leading `#`, a leading `0x`, or neither,
+/// naming `key` on failure.
+fn parse_color(key: &str, value: &str) -> Result<Color> {
+    let value = value.trim();
+    let digits = value.strip_prefix("0x").unwrap_or(value);
+    let hex = match digits.starts_with('#') {
+        true => digits.to_owned(),
+        false => format!("#{digits}"),
+    };
+    hex.parse()
+        .or_else(|_| value.parse())
+        .with_context(|| format!("invalid color for '{key}': '{value}'"))
+}
+
+/// Read `path` and import it with `parse`, naming the file and format on failure.
+fn load_with<T>(
+    path: &std::path::Path,
+    format: &str,
+    parse: impl FnOnce(&str) -> Result<T>,
+) -> Result<T> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("could not read scheme '{}'", path.display()))?;
+    parse(&content)
+        .with_context(|| format!("failed to import {format} scheme '{}'", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_slots_are_listed() {
+        let mut slots = Slots::default();
+        let red = Color::rgb(255, 0, 0);
+        assert!(slots.set("background", red));
+        assert!(slots.set("1", red));
+        assert!(slots.set("15", red));
+        assert!(!slots.set("16", red));
+        assert!(!slots.set("selection_background", red));
+
+        let imported = slots.into_imported("t", None);
+        assert_eq!(imported.missing.len(), 19 - 3);
+        assert_eq!(
+            imported.missing[..2],
+            ["primary.foreground", "primary.cursor"]
+        );
+        assert!(imported.missing.contains(&"bright.black".to_owned()));
+        assert!(!imported.missing.contains(&"normal.red".to_owned()));
+        let bright = imported.theme.ansi.unwrap().bright.unwrap();
+        assert_eq!(bright.white, Some(red));
+    }
+
+    #[test]
+    fn colors_accept_common_prefixes() {
+        for value in ["#1a2b3c", "1a2b3c", "0x1a2b3c", " #1A2B3C "] {
+            assert_eq!(parse_color("k", value).unwrap().to_string(), "#1a2b3c");
+        }
+        assert_eq!(parse_color("k", "red").unwrap().to_string(), "#ff0000");
+    }
+}
@@ -0,0 +1,151 @@
+//! X resources (`~/.Xresources`) color entries.
+//!
+//! Reads `foreground`, `background`, `cursorColor`, and `color0`–`color15`
+//! for any resource class or wildcard (`*.color1`, `URxvt*color1`,
+//! `XTerm.vt100.background`); when the same slot is set more than once the
+//! last entry wins, as with `xrdb`. `#define NAME value` macros are expanded,
+//! so base16-xresources style files import as well. `!` comments and other
+//! preprocessor lines are ignored. Colors may also use the X11
+//! `rgb:rr/gg/bb` form, with one to four hex digits per channel.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::{Color, Imported, Slots, load_with, parse_color};
+
+/// Read the color entries of the resource file at `path` into a theme called `name`.
+pub fn load(name: &str, path: &Path) -> Result<Imported> {
+    load_with(path, "Xresources", |content| {
+        from_resources_str(name, content)
+    })
+}
+
+/// Convert X resources source into a theme called `name`.
+pub fn from_resources_str(name: &str, content: &str) -> Result<Imported> {
+    let mut defines: HashMap<&str, &str> = HashMap::new();
+    let mut slots = Slots::default();
+    for (n, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if let Some(define) = line.strip_prefix("#define") {
+            let mut parts = define.split_whitespace();
+            if let (Some(name), Some(value)) = (parts.next(), parts.next()) {
+                defines.insert(name, value);
+            }
+            continue;
+        }
+        if line.is_empty() || line.starts_with('!') || line.starts_with('#') {
+            continue;
+        }
+        let Some((resource, value)) = line.split_once(':') else {
+            continue;
+        };
+        let key = resource
+            .trim()
+            .rsplit(['.', '*'])
+            .next()
+            .unwrap_or_default();
+        let slot = match key {
+            "cursorColor" => "cursor",
+            _ => key.strip_prefix("color").unwrap_or(key),
+        };
+        if !matches!(slot, "foreground" | "background" | "cursor") && slot.parse::<usize>().is_err()
+        {
+            continue;
+        }
+        let value = value.trim();
+        let value = defines.get(value).copied().unwrap_or(value);
+        let color =
+            parse_x_color(resource.trim(), value).with_context(|| format!("line {}", n + 1))?;
+        slots.set(slot, color);
+    }
+    Ok(slots.into_imported(name, None))
+}
+
+/// Parse an X11 `rgb:r/g/b` color, or any form [`parse_color`] accepts.
+fn parse_x_color(key: &str, value: &str) -> Result<Color> {
+    let Some(channels) = value.strip_prefix("rgb:") else {
+        return parse_color(key, value);
+    };
+    // Each channel has one to four hex digits, scaled to 0-255.
+    let scaled = |hex: &str| -> Option<u8> {
+        if !(1..=4).contains(&hex.len()) {
+            return None;
+        }
+        let max = (1u32 << (4 * hex.len())) - 1;
+        let v = u32::from_str_radix(hex, 16).ok()?;
+        u8::try_from((v * 255 + max / 2) / max).ok()
+    };
+    match channels.split('/').map(scaled).collect::<Option<Vec<_>>>() {
+        Some(rgb) if rgb.len() == 3 => Ok(Color::rgb(rgb[0], rgb[1], rgb[2])),
+        _ => anyhow::bail!("invalid color for '{key}': '{value}'"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_resources_with_any_class() {
+        let imported = from_resources_str(
+            "xr",
+            r#"
+! colors
+*.foreground:   #c5c8c6
+*background:    #1d1f21
+URxvt*cursorColor: #aeafad
+*.color0:       #282a2e
+XTerm.vt100.color1: #a54242
+*.color1:       #cc342b
+URxvt.font:     xft:FiraCode:size=11
+"#,
+        )
+        .unwrap();
+        let ansi = imported.theme.ansi.as_ref().unwrap();
+        let primary = ansi.primary.as_ref().unwrap();
+        assert_eq!(primary.foreground.unwrap().to_string(), "#c5c8c6");
+        assert_eq!(primary.background.unwrap().to_string(), "#1d1f21");
+        assert_eq!(primary.cursor.unwrap().to_string(), "#aeafad");
+        let normal = ansi.normal.as_ref().unwrap();
+        assert_eq!(normal.black.unwrap().to_string(), "#282a2e");
+        assert_eq!(normal.red.unwrap().to_string(), "#cc342b");
+        assert_eq!(imported.missing.len(), 14);
+    }
+
+    #[test]
+    fn x11_rgb_colors_are_read() {
+        let imported = from_resources_str(
+            "xr",
+            "*.background: rgb:1d/1f/21\n*.foreground: rgb:ffff/8000/0\n*.color1: rgb:a/b/c\n",
+        )
+        .unwrap();
+        let ansi = imported.theme.ansi.unwrap();
+        let primary = ansi.primary.unwrap();
+        assert_eq!(primary.background.unwrap().to_string(), "#1d1f21");
+        assert_eq!(primary.foreground.unwrap().to_string(), "#ff8000");
+        assert_eq!(ansi.normal.unwrap().red.unwrap().to_string(), "#aabbcc");
+
+        let err = from_resources_str("xr", "*.color2: rgb:12/34\n").unwrap_err();
+        assert!(
+            format!("{err:#}").contains("invalid color for '*.color2': 'rgb:12/34'"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn defines_are_expanded() {
+        let imported = from_resources_str(
+            "xr",
+            "#define base08 #ab4642\n#define base00 #181818\n\
+             *.background: base00\n*.color9: base08\n",
+        )
+        .unwrap();
+        let ansi = imported.theme.ansi.unwrap();
+        assert_eq!(
+            ansi.primary.unwrap().background.unwrap().to_string(),
+            "#181818"
+        );
+        assert_eq!(ansi.bright.unwrap().red.unwrap().to_string(), "#ab4642");
+    }
+}
@@ -0,0 +1,223 @@
+//! Theme inheritance via the top-level `extends` key.
+//!
+//! A theme may name a parent either by theme name (looked up in
+//! [`THEMES_DIR`]) or by a path relative to the extending file:
+//!
+//! ```toml
+//! name    = "neon-blue"
+//! extends = "neon"                # ~/.config/axtc/themes/neon.toml
+//! # extends = "../shared/base.toml"
+//!
+//! [polybar]
+//! primary_color = "#0090ff"
+//! ```
+//!
+//! Parents are resolved recursively and deep-merged table by table: keys set in
+//! the child win, tables present in both are merged, and every other value
+//! (including arrays) is replaced wholesale. The `extends` key itself is
+//! removed from the merged result.
+//!
+//! [`THEMES_DIR`]: crate::constants::THEMES_DIR
+
+use anyhow::{Context, Result, bail};
+use std::path::{Path, PathBuf};
+use toml::Table;
+
+use crate::constants::THEMES_DIR;
+
+/// Key naming a theme's parent.
+pub const EXTENDS_KEY: &str = "extends";
+
+/// Read the theme at `path`, resolving its `extends` chain into a single merged table.
+///
+/// Returns `None` when the theme has no parent, so callers can deserialize the
+/// original source and keep line numbers in their error messages.
+pub(crate) fn resolve(path: &Path, content: &str) -> Result<Option<Table>> {
+    let table: Table = toml::from_str(content)?;
+    if !table.contains_key(EXTENDS_KEY) {
+        return Ok(None);
+    }
+    let mut chain = vec![];
+    resolve_table(path, table, &mut chain).map(Some)
+}
+
+fn resolve_table(path: &Path, mut table: Table, chain: &mut Vec<PathBuf>) -> Result<Table> {
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if chain.contains(&canonical) {
+        chain.push(canonical);
+        bail!("theme inheritance cycle: {}", describe(chain));
+    }
+    chain.push(canonical);
+
+    let Some(parent) = table.remove(EXTENDS_KEY) else {
+        return Ok(table);
+    };
+    let parent = parent
+        .as_str()
+        .with_context(|| format!("'{EXTENDS_KEY}' in '{}' must be a string", path.display()))?;
+
+    let parent_path = parent_path(path, parent);
+    let content = std::fs::read_to_string(&parent_path).with_context(|| {
+        format!(
+            "parent theme '{parent}' not found at {} (inheritance chain: {} → {parent})",
+            parent_path.display(),
+            describe(chain)
+        )
+    })?;
+    let parent_table: Table = toml::from_str(&content)
+        .with_context(|| format!("failed to parse theme from '{}'", parent_path.display()))?;
+
+    let mut base = resolve_table(&parent_path, parent_table, chain)?;
+    merge(&mut base, table);
+    Ok(base)
+}
+
+/// Resolve an `extends` value to a file path.
+///
+/// Values ending in `.toml` or containing a path separator are taken relative to
+/// the directory of the extending theme; anything else is a theme name.
+fn parent_path(child: &Path, parent: &str) -> PathBuf {
+    if parent.ends_with(".toml") || parent.contains(std::path::MAIN_SEPARATOR) {
+        child
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(parent)
+    } else {
+        THEMES_DIR.join(format!("{parent}.toml"))
+    }
+}
+
+/// Deep-merge `overlay` into `base`. Tables merge recursively; everything else is replaced.
+pub(crate) fn merge(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(b)), toml::Value::Table(o)) => merge(b, o),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn describe(chain: &[PathBuf]) -> String {
+    chain
+        .iter()
+        .map(|p| {
+            p.file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_else(|| p.display().to_string())
+        })
+        .collect::<Vec<_>>()
+        .join(" → ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn load(path: &Path) -> Result<Table> {
+        let content = std::fs::read_to_string(path).unwrap();
+        Ok(resolve(path, &content)?.expect("theme should extend a parent"))
+    }
+
+    #[test]
+    fn theme_without_extends_is_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write(dir.path(), "plain.toml", r#"name = "plain""#);
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(resolve(&path, &content).unwrap().is_none());
+    }
+
+    #[test]
+    fn child_sections_merge_over_parent() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "base.toml",
+            r##"
+name = "base"
+
+[ansi.normal]
+red  = "#ff0000"
+blue = "#0000ff"
+
+[picom]
+blur = true
+"##,
+        );
+        let child = write(
+            dir.path(),
+            "child.toml",
+            r##"
+name    = "child"
+extends = "./base.toml"
+
+[ansi.normal]
+red = "#aa0000"
+"##,
+        );
+        let merged = load(&child).unwrap();
+        assert_eq!(merged["name"].as_str(), Some("child"));
+        assert!(!merged.contains_key(EXTENDS_KEY));
+        let normal = merged["ansi"]["normal"].as_table().unwrap();
+        assert_eq!(normal["red"].as_str(), Some("#aa0000"));
+        assert_eq!(normal["blue"].as_str(), Some("#0000ff"));
+        assert_eq!(merged["picom"]["blur"].as_bool(), Some(true));
+    }
+
+    #[test]
+    fn multi_level_chains_resolve() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "a.toml",
+            "name = \"a\"\n[polybar]\nheight = 20\n",
+        );
+        write(
+            dir.path(),
+            "b.toml",
+            "extends = \"a.toml\"\nname = \"b\"\n[polybar]\nposition = \"bottom\"\n",
+        );
+        let c = write(
+            dir.path(),
+            "c.toml",
+            "extends = \"b.toml\"\nname = \"c\"\n[polybar]\nheight = 30\n",
+        );
+        let merged = load(&c).unwrap();
+        assert_eq!(merged["name"].as_str(), Some("c"));
+        assert_eq!(merged["polybar"]["height"].as_integer(), Some(30));
+        assert_eq!(merged["polybar"]["position"].as_str(), Some("bottom"));
+    }
+
+    #[test]
+    fn cycles_are_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "a.toml", "name = \"a\"\nextends = \"b.toml\"\n");
+        let b = write(dir.path(), "b.toml", "name = \"b\"\nextends = \"a.toml\"\n");
+        let err = load(&b).unwrap_err().to_string();
+        assert_eq!(err, "theme inheritance cycle: b → a → b");
+    }
+
+    #[test]
+    fn missing_parent_reports_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "a.toml",
+            "name = \"a\"\nextends = \"gone.toml\"\n",
+        );
+        let b = write(dir.path(), "b.toml", "name = \"b\"\nextends = \"a.toml\"\n");
+        let err = load(&b).unwrap_err().to_string();
+        assert!(err.contains("parent theme 'gone.toml' not found"), "{err}");
+        assert!(
+            err.contains("inheritance chain: b → a → gone.toml"),
+            "{err}"
+        );
+    }
+}
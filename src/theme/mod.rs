@@ -1,30 +1,106 @@
 //! Theme loading and directory resolution.
 
+pub mod check;
+pub mod color;
 pub mod config;
+pub mod cvd;
+pub mod export;
+pub mod import;
+pub mod inherit;
+pub mod palette;
+pub mod reference;
+pub mod roles;
+pub mod snapshot;
+pub mod variant;
+pub use color::Color;
 pub use config::{
     AlacrittyConfig, AnsiConfig, BrightColors, GlobalConfig, HerbstluftwmConfig, NormalColors,
     PicomConfig, PolybarConfig, PrimaryColors, Theme,
 };
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, anyhow};
+use serde_path_to_error::Segment;
 use std::path::Path;
+use toml::de::{DeTable, DeValue};
 
 use crate::constants::THEMES_DIR;
 
 impl Theme {
     /// Load and deserialize a theme by name from the user's themes directory.
+    ///
+    /// Any `extends` chain is resolved and merged first; see [`inherit`].
     pub fn load(name: &str) -> Result<Self> {
         let path = THEMES_DIR.join(format!("{name}.toml"));
         let content = std::fs::read_to_string(&path)
             .with_context(|| format!("theme '{name}' not found at {}", path.display()))?;
-        toml::from_str(&content).with_context(|| format!("failed to parse theme '{name}'"))
+        Self::from_file_content(&path, &content)
+            .with_context(|| format!("failed to parse theme '{name}'"))
     }
 
     /// Load and deserialize a theme from an explicit file path.
+    ///
+    /// Any `extends` chain is resolved and merged first; see [`inherit`].
     pub fn load_from_path(path: &Path) -> Result<Self> {
         let content = std::fs::read_to_string(path)
             .with_context(|| format!("could not read theme file '{}'", path.display()))?;
-        toml::from_str(&content)
+        Self::from_file_content(path, &content)
             .with_context(|| format!("failed to parse theme from '{}'", path.display()))
     }
+
+    fn from_file_content(path: &Path, content: &str) -> Result<Self> {
+        match inherit::resolve(path, content)? {
+            Some(merged) => Self::from_table(content, merged),
+            None => Self::from_toml_str(content),
+        }
+    }
+
+    /// Deserialize a theme from TOML source.
+    ///
+    /// Errors name the dotted key path of the offending value (e.g.
+    /// `ansi.normal.red`) in addition to its line and column in `content`.
+    /// `${path}` references are resolved first; see
+    /// [`reference`](mod@reference).
+    pub fn from_toml_str(content: &str) -> Result<Self> {
+        if content.contains("${") {
+            return Self::from_table(content, toml::from_str(content)?);
+        }
+        let de = toml::Deserializer::parse(content)?;
+        Ok(serde_path_to_error::deserialize(de)?)
+    }
+
+    /// Deserialize an already merged `table` read from `content`, which is
+    /// only used to point errors at the offending line.
+    fn from_table(content: &str, mut table: toml::Table) -> Result<Self> {
+        reference::resolve(&mut table)?;
+        serde_path_to_error::deserialize(toml::Value::Table(table)).map_err(|err| {
+            match locate(content, err.path()) {
+                Some((line, column)) => anyhow!(
+                    "{}: line {line}, column {column}: {}",
+                    err.path(),
+                    err.inner().message()
+                ),
+                None => err.into(),
+            }
+        })
+    }
+}
+
+/// The line and column (both 1-based) of the value at `path` in `content`, if
+/// `content` sets it.
+fn locate(content: &str, path: &serde_path_to_error::Path) -> Option<(usize, usize)> {
+    let root = DeValue::Table(DeTable::parse(content).ok()?.into_inner());
+    let mut value = &root;
+    let mut start = 0;
+    for segment in path {
+        let next = match segment {
+            Segment::Map { key } => value.get(key)?,
+            Segment::Seq { index } => value.get(*index)?,
+            _ => return None,
+        };
+        start = next.span().start;
+        value = next.get_ref();
+    }
+    let before = &content[..start];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    Some((before.matches('\n').count() + 1, start - line_start + 1))
 }
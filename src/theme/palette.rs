@@ -0,0 +1,413 @@
+//! Theme generation from a wallpaper image, used by `axtc new --from-image`.
+//!
+//! The image is downscaled and split into up to sixteen dominant colors by
+//! median cut. The colors are then assigned to theme slots:
+//!
+//! | Slot                      | Source                                                   |
+//! |---------------------------|----------------------------------------------------------|
+//! | background                | most common color, pushed dark (light for bright images) |
+//! | foreground                | a faint tint of the background hue at the other extreme  |
+//! | red … cyan                | nearest saturated color by hue, or a synthesized one     |
+//! | black, white              | shades of the background and foreground                  |
+//! | cursor, polybar accent    | the most prominent saturated color                       |
+//!
+//! Every slot except the one shading the background (black on dark themes,
+//! white on light ones) is then adjusted until it reaches [`MIN_CONTRAST`]
+//! against the background; bright black only needs [`MIN_DIM_CONTRAST`].
+
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use super::{
+    AnsiConfig, BrightColors, Color, HerbstluftwmConfig, NormalColors, PolybarConfig,
+    PrimaryColors, Theme,
+};
+
+/// Minimum WCAG contrast ratio of generated colors against the background.
+pub const MIN_CONTRAST: f32 = 4.5;
+/// Minimum contrast for bright black, which is typically used for dimmed text.
+pub const MIN_DIM_CONTRAST: f32 = 3.0;
+
+const PALETTE_SIZE: usize = 16;
+const SAMPLE_SIZE: u32 = 128;
+/// How far (in degrees) an image color's hue may be from an ANSI slot to fill it.
+const HUE_TOLERANCE: f32 = 20.0;
+
+/// A dominant color and the share of sampled pixels it covers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Swatch {
+    /// Average color of the pixels in this group.
+    pub color: Color,
+    /// Fraction of all pixels, `0.0..=1.0`.
+    pub weight: f32,
+}
+
+/// Split `pixels` into at most `count` groups by median cut and return their
+/// average colors, most common first.
+pub fn median_cut(pixels: &[Color], count: usize) -> Vec<Swatch> {
+    let channels = |c: &Color| [c.r, c.g, c.b];
+    let range = |group: &[Color], ch: usize| {
+        let values = group.iter().map(|c| channels(c)[ch]);
+        values.clone().max().unwrap_or(0) - values.min().unwrap_or(0)
+    };
+
+    let mut groups = vec![pixels.to_vec()];
+    while groups.len() < count {
+        let widest = groups
+            .iter()
+            .enumerate()
+            .filter(|(_, g)| g.len() > 1)
+            .flat_map(|(i, g)| (0..3).map(move |ch| (i, ch, range(g, ch))))
+            .max_by_key(|&(i, ch, r)| (r, std::cmp::Reverse((i, ch))));
+        let Some((i, ch, r)) = widest else { break };
+        if r == 0 {
+            break;
+        }
+        let group = &mut groups[i];
+        group.sort_by_key(|c| channels(c)[ch]);
+        let upper = group.split_off(group.len() / 2);
+        groups.push(upper);
+    }
+
+    // A large uniform region can be cut in two; merge groups that average to
+    // the same color so its weight is not split.
+    let total = pixels.len().max(1) as f32;
+    let mut swatches: Vec<Swatch> = vec![];
+    for g in groups.iter().filter(|g| !g.is_empty()) {
+        let avg = |ch: usize| {
+            let sum: u32 = g.iter().map(|c| channels(c)[ch] as u32).sum();
+            (sum as f32 / g.len() as f32).round() as u8
+        };
+        let color = Color::rgb(avg(0), avg(1), avg(2));
+        let weight = g.len() as f32 / total;
+        match swatches.iter_mut().find(|s| s.color == color) {
+            Some(existing) => existing.weight += weight,
+            None => swatches.push(Swatch { color, weight }),
+        }
+    }
+    swatches.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+    swatches
+}
+
+impl Theme {
+    /// Generate a complete theme from the PNG or JPEG image at `path`.
+    ///
+    /// The theme's `herbstluftwm.background_src` points at the image, so
+    /// applying it also sets the wallpaper.
+    pub fn from_image(name: &str, path: &Path) -> Result<Self> {
+        let image = image::ImageReader::open(path)
+            .with_context(|| format!("could not open image '{}'", path.display()))?
+            .with_guessed_format()
+            .with_context(|| format!("could not read image '{}'", path.display()))?
+            .decode()
+            .with_context(|| format!("could not decode image '{}'", path.display()))?;
+        let pixels: Vec<Color> = image
+            .thumbnail(SAMPLE_SIZE, SAMPLE_SIZE)
+            .to_rgb8()
+            .pixels()
+            .map(|p| Color::rgb(p[0], p[1], p[2]))
+            .collect();
+        anyhow::ensure!(!pixels.is_empty(), "image '{}' is empty", path.display());
+
+        let source = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let file_name = source
+            .file_name()
+            .map(|f| f.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mut theme = Self::from_palette(name, &median_cut(&pixels, PALETTE_SIZE));
+        theme.description = Some(format!("Generated from {file_name}"));
+        theme.herbstluftwm = Some(HerbstluftwmConfig {
+            borders: None,
+            transparency: None,
+            background_src: Some(source.to_string_lossy().into_owned()),
+            terminal: None,
+            extra: Default::default(),
+        });
+        Ok(theme)
+    }
+
+    /// Build a theme's colors from a palette as returned by [`median_cut`].
+    pub fn from_palette(name: &str, palette: &[Swatch]) -> Self {
+        let dominant = palette.first().map_or(Color::rgb(0, 0, 0), |s| s.color);
+        let (h, s, l) = dominant.to_hsl();
+        let dark = l < 0.5;
+        let background = match dark {
+            true => Color::from_hsl(h, s.min(0.35), l.min(0.1), 255),
+            false => Color::from_hsl(h, s.min(0.35), l.max(0.92), 255),
+        };
+        let readable = |c: Color, min: f32| ensure_contrast(c, &background, min, dark);
+        let foreground = readable(
+            Color::from_hsl(h, s.min(0.15), if dark { 0.88 } else { 0.15 }, 255),
+            MIN_CONTRAST,
+        );
+
+        let saturated: Vec<(Swatch, (f32, f32, f32))> = palette
+            .iter()
+            .map(|s| (*s, s.color.to_hsl()))
+            .filter(|(_, (_, s, l))| *s >= 0.2 && (0.1..=0.9).contains(l))
+            .collect();
+        let fallback_saturation = match saturated.is_empty() {
+            true => 0.6,
+            false => saturated.iter().map(|(_, (_, s, _))| s).sum::<f32>() / saturated.len() as f32,
+        };
+        let hued = |target: f32| {
+            let found = saturated
+                .iter()
+                .map(|(sw, hsl)| (sw, hsl, hue_distance(hsl.0, target)))
+                .filter(|(_, _, d)| *d <= HUE_TOLERANCE)
+                .min_by(|a, b| a.2.total_cmp(&b.2).then(b.0.weight.total_cmp(&a.0.weight)));
+            let (s, l) = match found {
+                Some((_, &(_, s, l), _)) => (s, l),
+                None => (fallback_saturation, 0.5),
+            };
+            let hue = found.map_or(target, |(_, hsl, _)| hsl.0);
+            let l = match dark {
+                true => l.clamp(0.5, 0.7),
+                false => l.clamp(0.3, 0.45),
+            };
+            let normal = readable(Color::from_hsl(hue, s, l, 255), MIN_CONTRAST);
+            let bright = match dark {
+                true => normal.lighten(0.1),
+                false => normal.darken(0.08),
+            };
+            (normal, readable(bright, MIN_CONTRAST))
+        };
+        let [red, yellow, green, cyan, blue, magenta] =
+            [0.0, 60.0, 120.0, 180.0, 240.0, 300.0].map(hued);
+
+        let shift = |c: Color, amount: f32| match dark {
+            true => c.lighten(amount),
+            false => c.darken(amount),
+        };
+        let near_background = shift(background, 0.08);
+        let dim = readable(shift(background, 0.3), MIN_DIM_CONTRAST);
+        let near_foreground = readable(shift(foreground, -0.1), MIN_CONTRAST);
+        let (black, bright_black, white, bright_white) = match dark {
+            true => (
+                near_background,
+                dim,
+                near_foreground,
+                shift(foreground, 0.08),
+            ),
+            false => (foreground, dim, near_background, background),
+        };
+
+        let accent = saturated
+            .iter()
+            .max_by(|a, b| {
+                let score = |(sw, (_, s, _)): &(Swatch, (f32, f32, f32))| s * sw.weight.sqrt();
+                score(a).total_cmp(&score(b))
+            })
+            .map_or(blue.0, |(sw, _)| readable(sw.color, MIN_CONTRAST));
+
+        Theme {
+            name: name.to_owned(),
+            description: None,
+            global: None,
+            ansi: Some(AnsiConfig {
+                primary: Some(PrimaryColors {
+                    foreground: Some(foreground),
+                    background: Some(background),
+                    cursor: Some(accent),
+                    extra: Default::default(),
+                }),
+                normal: Some(NormalColors {
+                    black: Some(black),
+                    red: Some(red.0),
+                    green: Some(green.0),
+                    yellow: Some(yellow.0),
+                    blue: Some(blue.0),
+                    magenta: Some(magenta.0),
+                    cyan: Some(cyan.0),
+                    white: Some(white),
+                    extra: Default::default(),
+                }),
+                bright: Some(BrightColors {
+                    black: Some(bright_black),
+                    red: Some(red.1),
+                    green: Some(green.1),
+                    yellow: Some(yellow.1),
+                    blue: Some(blue.1),
+                    magenta: Some(magenta.1),
+                    cyan: Some(cyan.1),
+                    white: Some(bright_white),
+                    extra: Default::default(),
+                }),
+                extra: Default::default(),
+            }),
+            herbstluftwm: None,
+            polybar: Some(PolybarConfig {
+                position: None,
+                height: None,
+                font: None,
+                primary_color: Some(accent),
+                background_alt: Some(shift(background, 0.12)),
+                extra: Default::default(),
+            }),
+            alacritty: None,
+            picom: None,
+            roles: Default::default(),
+            variant: None,
+            variants: Default::default(),
+            extra: Default::default(),
+        }
+    }
+}
+
+/// Shortest angle between two hues, in degrees.
+fn hue_distance(a: f32, b: f32) -> f32 {
+    let d = (a - b).rem_euclid(360.0);
+    d.min(360.0 - d)
+}
+
+/// Move `color` away from `background` in lightness until it reaches `min` contrast.
+fn ensure_contrast(color: Color, background: &Color, min: f32, dark: bool) -> Color {
+    let mut color = color;
+    for _ in 0..50 {
+        if color.contrast(background) >= min {
+            break;
+        }
+        color = match dark {
+            true => color.lighten(0.02),
+            false => color.darken(0.02),
+        };
+    }
+    color
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_image(
+        dir: &Path,
+        name: &str,
+        fill: impl Fn(u32, u32) -> [u8; 3],
+    ) -> std::path::PathBuf {
+        let path = dir.join(name);
+        image::RgbImage::from_fn(64, 64, |x, y| image::Rgb(fill(x, y)))
+            .save(&path)
+            .unwrap();
+        path
+    }
+
+    fn colors(theme: &Theme) -> Vec<(&'static str, Color)> {
+        let ansi = theme.ansi.as_ref().unwrap();
+        let (n, b) = (ansi.normal.as_ref().unwrap(), ansi.bright.as_ref().unwrap());
+        vec![
+            (
+                "foreground",
+                ansi.primary.as_ref().unwrap().foreground.unwrap(),
+            ),
+            ("cursor", ansi.primary.as_ref().unwrap().cursor.unwrap()),
+            ("red", n.red.unwrap()),
+            ("green", n.green.unwrap()),
+            ("yellow", n.yellow.unwrap()),
+            ("blue", n.blue.unwrap()),
+            ("magenta", n.magenta.unwrap()),
+            ("cyan", n.cyan.unwrap()),
+            ("bright red", b.red.unwrap()),
+            ("bright cyan", b.cyan.unwrap()),
+        ]
+    }
+
+    #[test]
+    fn median_cut_finds_dominant_colors() {
+        let mut pixels = vec![Color::rgb(10, 20, 30); 75];
+        pixels.extend(vec![Color::rgb(200, 40, 40); 25]);
+        let palette = median_cut(&pixels, 4);
+        assert_eq!(palette.len(), 2);
+        assert_eq!(palette[0].color, Color::rgb(10, 20, 30));
+        assert_eq!(palette[0].weight, 0.75);
+        assert_eq!(palette[1].color, Color::rgb(200, 40, 40));
+    }
+
+    #[test]
+    fn dark_image_yields_readable_dark_theme() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_image(dir.path(), "night.png", |x, _| match x {
+            0..48 => [12, 16, 40],
+            48..56 => [220, 40, 50],
+            _ => [40, 120, 220],
+        });
+        let theme = Theme::from_image("night", &path).unwrap();
+
+        let bg = theme
+            .ansi
+            .as_ref()
+            .unwrap()
+            .primary
+            .as_ref()
+            .unwrap()
+            .background
+            .unwrap();
+        assert!(bg.luminance() < 0.05, "{bg}");
+        for (slot, color) in colors(&theme) {
+            assert!(
+                color.contrast(&bg) >= MIN_CONTRAST,
+                "{slot} {color} on {bg}"
+            );
+        }
+        let red = theme
+            .ansi
+            .as_ref()
+            .unwrap()
+            .normal
+            .as_ref()
+            .unwrap()
+            .red
+            .unwrap();
+        assert!(hue_distance(red.to_hsl().0, 356.0) < 5.0, "{red}");
+        let hlwm = theme.herbstluftwm.as_ref().unwrap();
+        assert!(hlwm.background_src.as_ref().unwrap().ends_with("night.png"));
+        assert_eq!(
+            theme.description.as_deref(),
+            Some("Generated from night.png")
+        );
+    }
+
+    #[test]
+    fn light_image_yields_readable_light_theme() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_image(dir.path(), "snow.jpg", |_, y| match y {
+            0..56 => [235, 235, 225],
+            _ => [90, 160, 90],
+        });
+        let theme = Theme::from_image("snow", &path).unwrap();
+
+        let bg = theme
+            .ansi
+            .as_ref()
+            .unwrap()
+            .primary
+            .as_ref()
+            .unwrap()
+            .background
+            .unwrap();
+        assert!(bg.luminance() > 0.7, "{bg}");
+        for (slot, color) in colors(&theme) {
+            assert!(
+                color.contrast(&bg) >= MIN_CONTRAST,
+                "{slot} {color} on {bg}"
+            );
+        }
+    }
+
+    #[test]
+    fn generated_theme_round_trips_through_toml() {
+        let palette = median_cut(&[Color::rgb(30, 30, 30)], PALETTE_SIZE);
+        let theme = Theme::from_palette("grey", &palette);
+        let reparsed = Theme::from_toml_str(&toml::to_string(&theme).unwrap()).unwrap();
+        let normal = reparsed.ansi.unwrap().normal.unwrap();
+        assert!(normal.magenta.is_some() && normal.white.is_some());
+    }
+
+    #[test]
+    fn unreadable_files_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.png");
+        std::fs::write(&path, "not an image").unwrap();
+        let err = Theme::from_image("x", &path).unwrap_err().to_string();
+        assert!(err.starts_with("could not decode image"), "{err}");
+    }
+}
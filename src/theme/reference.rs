@@ -0,0 +1,326 @@
+//! `${path}` references between theme values, resolved when a theme is loaded.
+//!
+//! Any string value may name another value by its dotted path. A string that is
+//! exactly one reference takes the referenced value as is; references inside a
+//! longer string are interpolated. Write `$${` for a literal `${`.
+//!
+//! ```toml
+//! [roles]
+//! accent        = "${ansi.normal.magenta}"
+//! border_active = "${roles.accent}"
+//!
+//! [global]
+//! terminal = "alacritty --class $${TERM_CLASS}"   # literal ${TERM_CLASS}
+//! ```
+//!
+//! References are followed through other references, and cycles are errors. A
+//! `${…}` naming a key the theme does not set is an error inside `[roles]` and
+//! is kept as written everywhere else, so a polybar `${colors.primary}` or a
+//! shell `${VAR}` in a pass-through section reaches templates unchanged. Each `[variants.<name>]` table is resolved against the
+//! theme with that variant merged in, so a role pointing at `ansi.normal.magenta`
+//! follows a variant that changes magenta.
+
+use anyhow::{Context, Result, bail};
+use std::collections::BTreeMap;
+use toml::{Table, Value};
+
+use super::inherit::merge;
+
+const VARIANTS_KEY: &str = "variants";
+
+/// The section whose references must all resolve.
+const ROLES_KEY: &str = "roles";
+
+/// Replace every reference in `table`, including inside its variants.
+pub(crate) fn resolve(table: &mut Table) -> Result<()> {
+    let variants = table.remove(VARIANTS_KEY);
+    let raw = table.clone();
+    resolve_table(table)?;
+
+    let Some(variants) = variants else {
+        return Ok(());
+    };
+    let Value::Table(variants) = variants else {
+        bail!("'{VARIANTS_KEY}' must be a table");
+    };
+    let mut resolved = Table::new();
+    for (name, overlay) in variants {
+        let Value::Table(overlay) = overlay else {
+            bail!("'{VARIANTS_KEY}.{name}' must be a table");
+        };
+        let mut full = raw.clone();
+        merge(&mut full, overlay);
+        resolve_table(&mut full).with_context(|| format!("in variant '{name}'"))?;
+        resolved.insert(name, Value::Table(diff(full, table)));
+    }
+    table.insert(VARIANTS_KEY.into(), Value::Table(resolved));
+    Ok(())
+}
+
+fn resolve_table(table: &mut Table) -> Result<()> {
+    let mut paths = vec![];
+    collect_paths(table, "", &mut paths);
+    let mut resolver = Resolver {
+        raw: table.clone(),
+        done: BTreeMap::new(),
+        stack: vec![],
+    };
+    for path in paths {
+        let value = resolver.resolve(&path)?;
+        *lookup_mut(table, &path).expect("collected path exists") = value;
+    }
+    Ok(())
+}
+
+/// Paths of every string value that contains `${`.
+fn collect_paths(table: &Table, prefix: &str, paths: &mut Vec<String>) {
+    for (key, value) in table {
+        let path = match prefix {
+            "" => key.clone(),
+            _ => format!("{prefix}.{key}"),
+        };
+        match value {
+            Value::String(s) if s.contains("${") => paths.push(path),
+            Value::Table(t) => collect_paths(t, &path, paths),
+            _ => {}
+        }
+    }
+}
+
+struct Resolver {
+    raw: Table,
+    done: BTreeMap<String, Value>,
+    stack: Vec<String>,
+}
+
+impl Resolver {
+    fn resolve(&mut self, path: &str) -> Result<Value> {
+        if let Some(value) = self.done.get(path) {
+            return Ok(value.clone());
+        }
+        if self.stack.iter().any(|p| p == path) {
+            self.stack.push(path.to_owned());
+            bail!("reference cycle: {}", self.stack.join(" → "));
+        }
+        let value = match lookup(&self.raw, path) {
+            Some(Value::String(s)) if s.contains("${") => {
+                let s = s.clone();
+                self.stack.push(path.to_owned());
+                let value = self.interpolate(path, &s)?;
+                self.stack.pop();
+                value
+            }
+            Some(value) => value.clone(),
+            None => bail!("'{path}' is not set"),
+        };
+        self.done.insert(path.to_owned(), value.clone());
+        Ok(value)
+    }
+
+    fn interpolate(&mut self, path: &str, s: &str) -> Result<Value> {
+        if let Some(target) = s.strip_prefix("${").and_then(|r| r.strip_suffix('}'))
+            && !target.contains(['$', '{', '}'])
+        {
+            return Ok(self
+                .target(path, target)?
+                .unwrap_or_else(|| Value::String(s.to_owned())));
+        }
+        let mut out = String::new();
+        let mut rest = s;
+        while let Some(i) = rest.find('$') {
+            out += &rest[..i];
+            rest = &rest[i..];
+            if let Some(after) = rest.strip_prefix("$${") {
+                out += "${";
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix("${") {
+                let Some(end) = after.find('}') else {
+                    if is_role(path) {
+                        bail!("'{path}' has an unterminated '${{' in \"{s}\"");
+                    }
+                    break;
+                };
+                out += &match self.target(path, &after[..end])? {
+                    None => rest[..end + 3].to_owned(),
+                    Some(Value::String(v)) => v,
+                    Some(v @ (Value::Integer(_) | Value::Float(_) | Value::Boolean(_))) => {
+                        v.to_string()
+                    }
+                    _ => bail!(
+                        "'{path}' interpolates '{}', which is not a string or number",
+                        &after[..end]
+                    ),
+                };
+                rest = &after[end + 1..];
+            } else {
+                out += "$";
+                rest = &rest[1..];
+            }
+        }
+        out += rest;
+        Ok(Value::String(out))
+    }
+
+    /// The resolved value of `target`, or `None` when it is unset and `path`
+    /// may keep the reference as written.
+    fn target(&mut self, path: &str, target: &str) -> Result<Option<Value>> {
+        let target = target.trim();
+        if lookup(&self.raw, target).is_none() {
+            if is_role(path) {
+                bail!("'{path}' references '{target}', which is not set");
+            }
+            return Ok(None);
+        }
+        match self.resolve(target)? {
+            Value::Table(_) => bail!("'{path}' references '{target}', which is a table"),
+            value => Ok(Some(value)),
+        }
+    }
+}
+
+fn is_role(path: &str) -> bool {
+    path.split('.').next() == Some(ROLES_KEY)
+}
+
+fn lookup<'a>(table: &'a Table, path: &str) -> Option<&'a Value> {
+    let (parents, key) = match path.rsplit_once('.') {
+        Some((parents, key)) => (Some(parents), key),
+        None => (None, path),
+    };
+    let table = match parents {
+        Some(parents) => parents
+            .split('.')
+            .try_fold(table, |t, k| t.get(k)?.as_table())?,
+        None => table,
+    };
+    table.get(key)
+}
+
+fn lookup_mut<'a>(table: &'a mut Table, path: &str) -> Option<&'a mut Value> {
+    let mut keys = path.split('.');
+    let mut value = table.get_mut(keys.next()?)?;
+    for key in keys {
+        value = value.as_table_mut()?.get_mut(key)?;
+    }
+    Some(value)
+}
+
+/// The keys of `full` whose values differ from `base`.
+fn diff(full: Table, base: &Table) -> Table {
+    full.into_iter()
+        .filter_map(|(key, value)| match (value, base.get(&key)) {
+            (Value::Table(t), Some(Value::Table(b))) => {
+                let t = diff(t, b);
+                (!t.is_empty()).then_some((key, Value::Table(t)))
+            }
+            (value, Some(b)) if &value == b => None,
+            (value, _) => Some((key, value)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolved(src: &str) -> Result<Table> {
+        let mut table: Table = toml::from_str(src).unwrap();
+        resolve(&mut table)?;
+        Ok(table)
+    }
+
+    #[test]
+    fn references_follow_chains_and_keep_types() {
+        let table = resolved(
+            r##"
+[ansi.normal]
+magenta = "#bd93f9"
+
+[roles]
+accent        = "${ansi.normal.magenta}"
+border_active = "${ roles.accent }"
+
+[polybar]
+height = 24
+label  = "h=${polybar.height} $${HOME}"
+copy   = "${polybar.height}"
+"##,
+        )
+        .unwrap();
+        assert_eq!(table["roles"]["border_active"].as_str(), Some("#bd93f9"));
+        assert_eq!(table["polybar"]["label"].as_str(), Some("h=24 ${HOME}"));
+        assert_eq!(table["polybar"]["copy"].as_integer(), Some(24));
+    }
+
+    #[test]
+    fn cycles_are_reported() {
+        let err = resolved("[roles]\na = \"${roles.b}\"\nb = \"${roles.a}\"\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "reference cycle: roles.a → roles.b → roles.a"
+        );
+    }
+
+    #[test]
+    fn unset_targets_are_reported() {
+        let err = resolved("[roles]\naccent = \"${ansi.normal.magneta}\"\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "'roles.accent' references 'ansi.normal.magneta', which is not set"
+        );
+    }
+
+    #[test]
+    fn unset_targets_outside_roles_are_kept() {
+        let table = resolved(
+            r##"
+[polybar]
+height = 24
+label  = "${colors.primary} ${polybar.height}"
+
+[rofi]
+theme = "${HOME}/.config/rofi/theme.rasi"
+color = "${colors.primary}"
+open  = "${unterminated"
+"##,
+        )
+        .unwrap();
+        assert_eq!(
+            table["polybar"]["label"].as_str(),
+            Some("${colors.primary} 24")
+        );
+        assert_eq!(
+            table["rofi"]["theme"].as_str(),
+            Some("${HOME}/.config/rofi/theme.rasi")
+        );
+        assert_eq!(table["rofi"]["color"].as_str(), Some("${colors.primary}"));
+        assert_eq!(table["rofi"]["open"].as_str(), Some("${unterminated"));
+    }
+
+    #[test]
+    fn variants_resolve_against_their_own_values() {
+        let table = resolved(
+            r##"
+[ansi.normal]
+magenta = "#bd93f9"
+red     = "#ff5555"
+
+[roles]
+accent = "${ansi.normal.magenta}"
+
+[variants.light.ansi.normal]
+magenta = "#8000c0"
+"##,
+        )
+        .unwrap();
+        assert_eq!(table["roles"]["accent"].as_str(), Some("#bd93f9"));
+        let light = table["variants"]["light"].as_table().unwrap();
+        assert_eq!(light["roles"]["accent"].as_str(), Some("#8000c0"));
+        assert!(
+            !light["ansi"]["normal"]
+                .as_table()
+                .unwrap()
+                .contains_key("red")
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! Semantic color roles, exposed to templates as `roles.*`.
+//!
+//! Templates ask for what a color is for (`roles.accent`, `roles.urgent`)
+//! rather than which palette slot holds it, so a theme can change its accent
+//! without touching templates. Roles the `[roles]` section does not set fall
+//! back to the palette:
+//!
+//! | Role              | Default                    |
+//! |-------------------|----------------------------|
+//! | `accent`          | `ansi.normal.magenta`      |
+//! | `urgent`          | `ansi.normal.red`          |
+//! | `surface`         | `ansi.primary.background`  |
+//! | `text`            | `ansi.primary.foreground`  |
+//! | `muted`           | `ansi.bright.black`        |
+//! | `border_active`   | `roles.accent`             |
+//! | `border_inactive` | `roles.surface`            |
+//!
+//! Role values are colors, usually written as [references](super::reference):
+//!
+//! ```toml
+//! [roles]
+//! accent  = "${ansi.normal.cyan}"
+//! surface = "#16161e"
+//! ```
+//!
+//! Themes may define roles beyond these; they reach templates the same way.
+
+use std::collections::BTreeMap;
+
+use super::{Color, Theme};
+
+impl Theme {
+    /// Every role this theme defines, with unset ones filled in from the palette
+    /// as listed in the [module docs](self). Roles whose source is unset are
+    /// omitted.
+    pub fn resolved_roles(&self) -> BTreeMap<String, Color> {
+        let ansi = self.ansi.as_ref();
+        let primary = ansi.and_then(|a| a.primary.as_ref());
+        let defaults = [
+            ("accent", ansi.and_then(|a| a.normal.as_ref()?.magenta)),
+            ("urgent", ansi.and_then(|a| a.normal.as_ref()?.red)),
+            ("surface", primary.and_then(|p| p.background)),
+            ("text", primary.and_then(|p| p.foreground)),
+            ("muted", ansi.and_then(|a| a.bright.as_ref()?.black)),
+        ];
+
+        let mut roles = self.roles.clone();
+        for (role, color) in defaults {
+            if let Some(color) = color {
+                roles.entry(role.to_owned()).or_insert(color);
+            }
+        }
+        for (role, source) in [("border_active", "accent"), ("border_inactive", "surface")] {
+            if let Some(&color) = roles.get(source) {
+                roles.entry(role.to_owned()).or_insert(color);
+            }
+        }
+        roles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unset_roles_come_from_the_palette() {
+        let theme = Theme::from_toml_str(
+            r##"
+name = "roles"
+
+[ansi.primary]
+background = "#101010"
+
+[ansi.normal]
+magenta = "#bd93f9"
+red     = "#ff5555"
+
+[roles]
+accent = "${ansi.normal.red}"
+"##,
+        )
+        .unwrap();
+        let roles = theme.resolved_roles();
+        let hex = |role: &str| roles[role].to_string();
+        assert_eq!(hex("accent"), "#ff5555");
+        assert_eq!(hex("border_active"), "#ff5555");
+        assert_eq!(hex("urgent"), "#ff5555");
+        assert_eq!(hex("border_inactive"), "#101010");
+        assert!(!roles.contains_key("text"));
+    }
+}
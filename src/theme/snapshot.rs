@@ -0,0 +1,422 @@
+//! Capture the configs already on disk as a theme, used by `axtc snapshot`.
+//!
+//! Reads the main config file of each built-in app and fills the theme
+//! sections the templates would have produced it from:
+//!
+//! - `alacritty/alacritty.toml`: `[ansi.*]` and `alacritty.font`, `font_style`,
+//!   `font_size` and `opacity`.
+//! - `picom/picom.conf`: `picom.transparency`, `blur` and `corner_radius`.
+//! - `polybar/config.ini`: `polybar.height`, `position`, `font`,
+//!   `primary_color` and `background_alt`, and the `urgent` and `muted` roles.
+//! - `herbstluftwm/autostart`: `herbstluftwm.terminal` and `background_src`,
+//!   `global.font`, and the `accent`, `urgent` and `border_active` roles.
+//!
+//! Alacritty's colors win over the background and foreground polybar and
+//! herbstluftwm use; those only fill slots alacritty leaves unset. Every app
+//! whose file exists gets its section, even when nothing could be read from
+//! it, so applying the captured theme themes the same apps.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use strum::IntoEnumIterator;
+use toml::{Table, Value};
+
+use super::import::alacritty::from_toml_str as alacritty_colors;
+use super::{
+    AlacrittyConfig, AnsiConfig, Color, GlobalConfig, HerbstluftwmConfig, PicomConfig,
+    PolybarConfig, PrimaryColors, Theme,
+};
+use crate::apply::App;
+
+/// A theme captured from the config files under a directory.
+#[derive(Debug)]
+pub struct Captured {
+    /// The captured theme.
+    pub theme: Theme,
+    /// Config files that were read.
+    pub read: Vec<PathBuf>,
+    /// Config files that do not exist, whose apps are left out of the theme.
+    pub missing: Vec<PathBuf>,
+}
+
+/// Build a theme called `name` from the built-in apps' config files under
+/// `root` (normally the config directory).
+pub fn capture(name: &str, root: &Path) -> Result<Captured> {
+    let mut captured = Captured {
+        theme: Theme {
+            name: name.to_owned(),
+            description: None,
+            global: None,
+            ansi: None,
+            herbstluftwm: None,
+            polybar: None,
+            alacritty: None,
+            picom: None,
+            roles: Default::default(),
+            variant: None,
+            variants: Default::default(),
+            extra: Default::default(),
+        },
+        read: vec![],
+        missing: vec![],
+    };
+    for app in App::iter() {
+        // The first file of each app is its main config; the rest are scripts.
+        let rel_path = &app.get_config_file_rel_paths()[0].rel_path;
+        let path = root.join(rel_path);
+        if !path.is_file() {
+            captured.missing.push(path);
+            continue;
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("could not read '{}'", path.display()))?;
+        let theme = &mut captured.theme;
+        match app {
+            App::Herbstluftwm => herbstluftwm(theme, &content),
+            App::Polybar => polybar(theme, &content),
+            App::Alacritty => alacritty(theme, &content)
+                .with_context(|| format!("failed to capture '{}'", path.display()))?,
+            App::Picom => picom(theme, &content),
+        }
+        captured.read.push(path);
+    }
+    Ok(captured)
+}
+
+fn alacritty(theme: &mut Theme, content: &str) -> Result<()> {
+    let config: Table = toml::from_str(content)?;
+    if config.contains_key("colors") {
+        let ansi = alacritty_colors(&theme.name, content)?.theme.ansi;
+        if let Some(ansi) = ansi {
+            merge_ansi(theme, ansi);
+        }
+    }
+
+    let font = config.get("font");
+    let normal = font.and_then(|f| f.get("normal"));
+    let string = |v: Option<&Value>, key: &str| {
+        v.and_then(|v| v.get(key))
+            .and_then(Value::as_str)
+            .map(str::to_owned)
+    };
+    theme.alacritty = Some(AlacrittyConfig {
+        font: string(normal, "family"),
+        font_style: string(normal, "style"),
+        font_size: font.and_then(|f| f.get("size")).and_then(as_f32),
+        opacity: config
+            .get("window")
+            .and_then(|w| w.get("opacity"))
+            .and_then(as_f32),
+        extra: Default::default(),
+    });
+    Ok(())
+}
+
+/// Alacritty's colors replace whatever was captured before them.
+fn merge_ansi(theme: &mut Theme, ansi: AnsiConfig) {
+    if let Some(p) = ansi.primary {
+        let primary = primary(theme);
+        primary.foreground = p.foreground.or(primary.foreground);
+        primary.background = p.background.or(primary.background);
+        primary.cursor = p.cursor.or(primary.cursor);
+    }
+    let current = theme.ansi.get_or_insert_with(empty_ansi);
+    current.normal = ansi.normal.or(current.normal.take());
+    current.bright = ansi.bright.or(current.bright.take());
+}
+
+fn picom(theme: &mut Theme, content: &str) {
+    let mut picom = PicomConfig {
+        transparency: Some(false),
+        blur: Some(false),
+        corner_radius: None,
+        extra: Default::default(),
+    };
+    for (key, value) in content.lines().filter_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        Some((key.trim(), unquote(value.trim().trim_end_matches(';'))))
+    }) {
+        match key {
+            "inactive-opacity" => {
+                picom.transparency = value.parse::<f32>().ok().map(|o| o < 1.0);
+            }
+            "blur-background" if value == "true" => picom.blur = Some(true),
+            "blur-method" if value != "none" => picom.blur = Some(true),
+            "corner-radius" => picom.corner_radius = value.parse().ok(),
+            _ => {}
+        }
+    }
+    theme.picom = Some(picom);
+}
+
+fn polybar(theme: &mut Theme, content: &str) {
+    let mut polybar = PolybarConfig {
+        position: None,
+        height: None,
+        font: None,
+        primary_color: None,
+        background_alt: None,
+        extra: Default::default(),
+    };
+    let (mut section, mut in_first_bar, mut bars) = (String::new(), false, 0);
+    for line in content.lines().map(str::trim) {
+        if line.starts_with([';', '#']) {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.to_owned();
+            if section.starts_with("bar/") {
+                bars += 1;
+            }
+            in_first_bar = section.starts_with("bar/") && bars == 1;
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let (key, value) = (key.trim(), unquote(value.trim()));
+        match section.as_str() {
+            "colors" => {
+                let Some(color) = polybar_color(value) else {
+                    continue;
+                };
+                match key {
+                    "background" => fill(&mut primary(theme).background, color),
+                    "foreground" => fill(&mut primary(theme).foreground, color),
+                    "primary" => polybar.primary_color = Some(color),
+                    "background-alt" => polybar.background_alt = Some(color),
+                    "alert" => fill_role(theme, "urgent", color),
+                    "disabled" => fill_role(theme, "muted", color),
+                    _ => {}
+                }
+            }
+            _ if in_first_bar => match key {
+                "height" if !value.ends_with('%') => {
+                    let digits: String = value.chars().take_while(char::is_ascii_digit).collect();
+                    polybar.height = digits.parse().ok();
+                }
+                "bottom" => {
+                    let bottom = value == "true";
+                    polybar.position = Some(if bottom { "bottom" } else { "top" }.to_owned());
+                }
+                "font-0" => polybar.font = Some(value.to_owned()),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+    theme.polybar = Some(polybar);
+}
+
+/// A polybar color, which puts the alpha channel first in `#aarrggbb`.
+/// `${…}` references and other non-colors are skipped.
+fn polybar_color(value: &str) -> Option<Color> {
+    match value.strip_prefix('#') {
+        Some(hex) if hex.len() == 8 => format!("#{}{}", &hex[2..], &hex[..2]).parse().ok(),
+        _ => value.parse().ok(),
+    }
+}
+
+fn herbstluftwm(theme: &mut Theme, content: &str) {
+    let mut herbstluftwm = HerbstluftwmConfig {
+        borders: None,
+        transparency: None,
+        background_src: None,
+        terminal: None,
+        extra: Default::default(),
+    };
+    for line in content
+        .lines()
+        .map(|l| l.trim().trim_end_matches('&').trim_end())
+    {
+        let line = line
+            .strip_prefix("hc ")
+            .or_else(|| line.strip_prefix("herbstclient "))
+            .unwrap_or(line);
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["xsetroot", "-solid", color, ..] => {
+                if let Ok(color) = unquote(color).parse() {
+                    fill(&mut primary(theme).background, color);
+                }
+            }
+            ["nitrogen", mode, .., path] if mode.starts_with("--set-") => {
+                herbstluftwm.background_src = Some(unquote(path).to_owned());
+            }
+            ["keybind", key, "spawn", command, ..] if key.ends_with("-Return") => {
+                herbstluftwm.terminal = Some(unquote(command).to_owned());
+            }
+            ["attr", "theme.title_font", font, ..] => {
+                let family = unquote(font).split(':').next().unwrap_or_default();
+                theme.global.get_or_insert_with(|| GlobalConfig {
+                    font: Some(family.to_owned()),
+                    extra: Default::default(),
+                });
+            }
+            ["attr", attr, color, ..] => {
+                let role = match *attr {
+                    "theme.active.color" => "accent",
+                    "theme.urgent.color" => "urgent",
+                    "theme.active.inner_color" => "border_active",
+                    _ => continue,
+                };
+                if let Ok(color) = unquote(color).parse::<Color>() {
+                    fill_role(theme, role, Color::rgb(color.r, color.g, color.b));
+                }
+            }
+            _ => {}
+        }
+    }
+    theme.herbstluftwm = Some(herbstluftwm);
+}
+
+fn empty_ansi() -> AnsiConfig {
+    AnsiConfig {
+        primary: None,
+        normal: None,
+        bright: None,
+        extra: Default::default(),
+    }
+}
+
+fn primary(theme: &mut Theme) -> &mut PrimaryColors {
+    theme
+        .ansi
+        .get_or_insert_with(empty_ansi)
+        .primary
+        .get_or_insert_with(|| PrimaryColors {
+            foreground: None,
+            background: None,
+            cursor: None,
+            extra: Default::default(),
+        })
+}
+
+fn fill(slot: &mut Option<Color>, color: Color) {
+    slot.get_or_insert(color);
+}
+
+fn fill_role(theme: &mut Theme, role: &str, color: Color) {
+    theme.roles.entry(role.to_owned()).or_insert(color);
+}
+
+fn unquote(value: &str) -> &str {
+    value.trim_matches(['"', '\''])
+}
+
+fn as_f32(value: &Value) -> Option<f32> {
+    value
+        .as_float()
+        .map(|f| f as f32)
+        .or_else(|| value.as_integer().map(|i| i as f32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template::search::TemplateSearch;
+
+    #[test]
+    fn reads_hand_written_configs() {
+        let mut theme = Theme::from_toml_str("name = \"t\"").unwrap();
+        picom(
+            &mut theme,
+            "inactive-opacity = 0.85;\nblur-method = \"dual_kawase\";\ncorner-radius = 10;\n",
+        );
+        polybar(
+            &mut theme,
+            "[colors]\nbackground = #cc1d1f21\nprimary = ${colors.fg}\n\n\
+             [bar/main]\nheight = 30\nbottom = true\n\n[bar/other]\nheight = 50\n",
+        );
+        let picom = theme.picom.as_ref().unwrap();
+        assert_eq!(
+            (picom.transparency, picom.blur, picom.corner_radius),
+            (Some(true), Some(true), Some(10))
+        );
+        let polybar = theme.polybar.as_ref().unwrap();
+        assert_eq!(polybar.height, Some(30));
+        assert_eq!(polybar.position.as_deref(), Some("bottom"));
+        assert_eq!(polybar.primary_color, None);
+        let background = theme.ansi.as_ref().unwrap().primary.as_ref().unwrap();
+        assert_eq!(background.background.unwrap().to_string(), "#1d1f21cc");
+
+        alacritty(&mut theme, "[colors.cursor]\ncursor = \"CellForeground\"\n").unwrap();
+        let primary = theme.ansi.as_ref().unwrap().primary.as_ref().unwrap();
+        assert_eq!(primary.cursor, None);
+    }
+
+    #[test]
+    fn missing_files_are_reported() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("picom")).unwrap();
+        std::fs::write(tmp.path().join("picom/picom.conf"), "corner-radius = 4;").unwrap();
+        let captured = capture("mine", tmp.path()).unwrap();
+        assert_eq!(captured.read, [tmp.path().join("picom/picom.conf")]);
+        assert_eq!(captured.missing.len(), 3);
+        assert!(captured.theme.alacritty.is_none());
+        assert_eq!(captured.theme.picom.unwrap().corner_radius, Some(4));
+    }
+
+    #[test]
+    fn rendered_configs_capture_their_theme() {
+        let neon =
+            Theme::from_toml_str(include_str!("../../themes/neon.toml")).expect("bundled theme");
+        let tmp = tempfile::tempdir().unwrap();
+        let templates = TemplateSearch::with_dirs(None, tmp.path().join("none"));
+        for app in App::iter() {
+            for cfg in crate::apply::AppSpec::from(app)
+                .render_theme(&neon, &templates)
+                .unwrap()
+            {
+                let dest = tmp.path().join(&cfg.rel_path);
+                std::fs::create_dir_all(dest.parent().unwrap()).unwrap();
+                std::fs::write(dest, cfg.content).unwrap();
+            }
+        }
+
+        let captured = capture("neon-copy", tmp.path()).unwrap().theme;
+        assert!(captured.ansi.is_some());
+        let (ansi, neon_ansi) = (captured.ansi.unwrap(), neon.ansi.unwrap());
+        let (p, np) = (ansi.primary.unwrap(), neon_ansi.primary.unwrap());
+        assert_eq!(
+            (p.foreground, p.background, p.cursor),
+            (np.foreground, np.background, np.cursor)
+        );
+        assert_eq!(
+            ansi.bright.unwrap().magenta,
+            neon_ansi.bright.unwrap().magenta
+        );
+
+        let (picom, neon_picom) = (captured.picom.unwrap(), neon.picom.unwrap());
+        assert_eq!(picom.transparency, neon_picom.transparency);
+        assert_eq!(picom.blur, neon_picom.blur);
+        assert_eq!(picom.corner_radius, neon_picom.corner_radius);
+
+        let (polybar, neon_polybar) = (captured.polybar.unwrap(), neon.polybar.unwrap());
+        assert_eq!(polybar.height, neon_polybar.height);
+        assert_eq!(polybar.position, neon_polybar.position);
+        assert_eq!(polybar.primary_color, neon_polybar.primary_color);
+        assert_eq!(polybar.background_alt, neon_polybar.background_alt);
+
+        let alacritty = captured.alacritty.unwrap();
+        assert_eq!(alacritty.font.as_deref(), Some("FiraCode"));
+        assert_eq!(
+            alacritty.font_size,
+            neon.alacritty.as_ref().unwrap().font_size
+        );
+        assert_eq!(alacritty.opacity, neon.alacritty.as_ref().unwrap().opacity);
+
+        let herbstluftwm = captured.herbstluftwm.unwrap();
+        let neon_herbstluftwm = neon.herbstluftwm.unwrap();
+        assert_eq!(herbstluftwm.terminal, neon_herbstluftwm.terminal);
+        assert_eq!(
+            herbstluftwm.background_src,
+            neon_herbstluftwm.background_src
+        );
+        assert_eq!(captured.global.unwrap().font.as_deref(), Some("FiraCode"));
+        assert_eq!(
+            captured.roles.get("accent"),
+            neon_ansi.normal.unwrap().magenta.as_ref()
+        );
+    }
+}
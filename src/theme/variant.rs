@@ -0,0 +1,128 @@
+//! Palette variants kept in a single theme file, selected with
+//! `axtc apply --variant <name>`.
+//!
+//! Each `[variants.<name>]` table holds ordinary theme sections. Selecting a
+//! variant deep-merges it over the rest of the theme, the same way
+//! [`extends`](super::inherit) merges a child over its parent:
+//!
+//! ```toml
+//! name    = "neon"
+//! variant = "dark"        # optional: names the base palette
+//!
+//! [ansi.primary]
+//! background = "#080808"
+//! foreground = "#e0e0e0"
+//!
+//! [variants.light.ansi.primary]
+//! background = "#f4f4f4"
+//! foreground = "#202020"
+//! ```
+//!
+//! Templates see the selected name as `variant`.
+
+use anyhow::{Context, Result, bail};
+use toml::Table;
+
+use super::Theme;
+use super::inherit::merge;
+
+impl Theme {
+    /// Names of the variants this theme defines, sorted.
+    pub fn variant_names(&self) -> Vec<&str> {
+        self.variants.keys().map(String::as_str).collect()
+    }
+
+    /// This theme with the variant `name` merged over it.
+    ///
+    /// The result has `variant` set to `name` and no `variants` of its own.
+    pub fn with_variant(mut self, name: &str) -> Result<Self> {
+        let Some(overlay) = self.variants.remove(name) else {
+            let available = match self.variants.is_empty() {
+                true => "it has no variants".to_owned(),
+                false => format!("available: {}", self.variant_names().join(", ")),
+            };
+            bail!(
+                "theme '{}' has no variant '{name}' ({available})",
+                self.name
+            );
+        };
+        self.variants.clear();
+        let mut table = Table::try_from(&self).context("could not serialize theme")?;
+        merge(&mut table, overlay);
+        table.insert("variant".into(), name.into());
+        serde_path_to_error::deserialize(toml::Value::Table(table))
+            .with_context(|| format!("invalid variant '{name}' of theme '{}'", self.name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NEON: &str = r##"
+name    = "neon"
+variant = "dark"
+
+[ansi.primary]
+background = "#080808"
+foreground = "#e0e0e0"
+
+[polybar]
+primary_color = "#ff1a3e"
+
+[variants.light.ansi.primary]
+background = "#f4f4f4"
+
+[variants.mono.polybar]
+primary_color = "#888888"
+"##;
+
+    #[test]
+    fn variant_merges_over_base() {
+        let theme = Theme::from_toml_str(NEON).unwrap();
+        assert_eq!(theme.variant_names(), ["light", "mono"]);
+        let light = theme.with_variant("light").unwrap();
+        assert_eq!(light.variant.as_deref(), Some("light"));
+        assert!(light.variants.is_empty());
+        let primary = light.ansi.unwrap().primary.unwrap();
+        assert_eq!(primary.background.unwrap().to_string(), "#f4f4f4");
+        assert_eq!(primary.foreground.unwrap().to_string(), "#e0e0e0");
+        assert_eq!(
+            light.polybar.unwrap().primary_color.unwrap().to_string(),
+            "#ff1a3e"
+        );
+    }
+
+    #[test]
+    fn unknown_variant_lists_available() {
+        let err = Theme::from_toml_str(NEON)
+            .unwrap()
+            .with_variant("dim")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "theme 'neon' has no variant 'dim' (available: light, mono)"
+        );
+        let plain = Theme::from_toml_str("name = \"plain\"").unwrap();
+        assert!(
+            plain
+                .with_variant("light")
+                .unwrap_err()
+                .to_string()
+                .ends_with("(it has no variants)")
+        );
+    }
+
+    #[test]
+    fn invalid_variant_values_are_reported() {
+        let theme =
+            Theme::from_toml_str("name = \"t\"\n[variants.bad.ansi.normal]\nred = \"nope\"\n")
+                .unwrap();
+        let err = format!("{:#}", theme.with_variant("bad").unwrap_err());
+        assert!(
+            err.starts_with("invalid variant 'bad' of theme 't'"),
+            "{err}"
+        );
+        assert!(err.contains("ansi.normal.red"), "{err}");
+    }
+}
@@ -19,6 +19,10 @@ fn minimal_theme() -> Theme {
         polybar: None,
         alacritty: None,
         picom: None,
+        roles: Default::default(),
+        variant: None,
+        variants: Default::default(),
+        extra: Default::default(),
     }
 }
 
@@ -39,12 +43,14 @@ fn ansi_primary_colors_render() {
     let mut theme = minimal_theme();
     theme.ansi = Some(AnsiConfig {
         primary: Some(PrimaryColors {
-            background: Some("#282a36".into()),
-            foreground: Some("#f8f8f2".into()),
+            background: Some("#282a36".parse().unwrap()),
+            foreground: Some("#f8f8f2".parse().unwrap()),
             cursor: None,
+            extra: Default::default(),
         }),
         normal: None,
         bright: None,
+        extra: Default::default(),
     });
     let out = render(&template_path(), &theme).unwrap();
     assert!(out.contains(r##"background = "#282a36""##));
@@ -57,16 +63,18 @@ fn ansi_normal_colors_render() {
     theme.ansi = Some(AnsiConfig {
         primary: None,
         normal: Some(NormalColors {
-            black: Some("#000000".into()),
-            red: Some("#ff5555".into()),
-            green: Some("#50fa7b".into()),
-            yellow: Some("#f1fa8c".into()),
-            blue: Some("#6272a4".into()),
-            magenta: Some("#ff79c6".into()),
-            cyan: Some("#8be9fd".into()),
-            white: Some("#bfbfbf".into()),
+            black: Some("#000000".parse().unwrap()),
+            red: Some("#ff5555".parse().unwrap()),
+            green: Some("#50fa7b".parse().unwrap()),
+            yellow: Some("#f1fa8c".parse().unwrap()),
+            blue: Some("#6272a4".parse().unwrap()),
+            magenta: Some("#ff79c6".parse().unwrap()),
+            cyan: Some("#8be9fd".parse().unwrap()),
+            white: Some("#bfbfbf".parse().unwrap()),
+            extra: Default::default(),
         }),
         bright: None,
+        extra: Default::default(),
     });
     let out = render(&template_path(), &theme).unwrap();
     assert!(out.contains("[colors.normal]"));
@@ -81,15 +89,17 @@ fn ansi_bright_colors_render() {
         primary: None,
         normal: None,
         bright: Some(BrightColors {
-            black: Some("#4d4d4d".into()),
-            red: Some("#ff6e6e".into()),
-            green: Some("#69ff94".into()),
-            yellow: Some("#ffffa5".into()),
-            blue: Some("#d6acff".into()),
-            magenta: Some("#ff92df".into()),
-            cyan: Some("#a4ffff".into()),
-            white: Some("#ffffff".into()),
+            black: Some("#4d4d4d".parse().unwrap()),
+            red: Some("#ff6e6e".parse().unwrap()),
+            green: Some("#69ff94".parse().unwrap()),
+            yellow: Some("#ffffa5".parse().unwrap()),
+            blue: Some("#d6acff".parse().unwrap()),
+            magenta: Some("#ff92df".parse().unwrap()),
+            cyan: Some("#a4ffff".parse().unwrap()),
+            white: Some("#ffffff".parse().unwrap()),
+            extra: Default::default(),
         }),
+        extra: Default::default(),
     });
     let out = render(&template_path(), &theme).unwrap();
     assert!(out.contains("[colors.bright]"));
@@ -103,12 +113,14 @@ fn app_font_takes_priority_over_global() {
     let mut theme = minimal_theme();
     theme.global = Some(GlobalConfig {
         font: Some("GlobalFont".into()),
+        extra: Default::default(),
     });
     theme.alacritty = Some(AlacrittyConfig {
         font: Some("AppFont".into()),
         font_size: None,
         font_style: None,
         opacity: None,
+        extra: Default::default(),
     });
     let out = render(&template_path(), &theme).unwrap();
     assert!(out.contains("AppFont"));
@@ -120,12 +132,14 @@ fn falls_back_to_global_font() {
     let mut theme = minimal_theme();
     theme.global = Some(GlobalConfig {
         font: Some("GlobalFont".into()),
+        extra: Default::default(),
     });
     theme.alacritty = Some(AlacrittyConfig {
         font: None,
         font_size: None,
         font_style: None,
         opacity: None,
+        extra: Default::default(),
     });
     let out = render(&template_path(), &theme).unwrap();
     assert!(out.contains("GlobalFont"));
@@ -147,6 +161,7 @@ fn font_style_defaults_to_semibold() {
         font_size: None,
         font_style: None,
         opacity: None,
+        extra: Default::default(),
     });
     let out = render(&template_path(), &theme).unwrap();
     assert!(out.contains("style = \"SemiBold\""));
@@ -160,6 +175,7 @@ fn custom_font_style_overrides_default() {
         font_size: None,
         font_style: Some("Light".into()),
         opacity: None,
+        extra: Default::default(),
     });
     let out = render(&template_path(), &theme).unwrap();
     assert!(out.contains("style = \"Light\""));
@@ -176,6 +192,7 @@ fn opacity_value_renders() {
         font_size: None,
         font_style: None,
         opacity: Some(0.5),
+        extra: Default::default(),
     });
     let out = render(&template_path(), &theme).unwrap();
     assert!(out.contains("[window]"));
@@ -0,0 +1,45 @@
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// Run the axtc binary in `dir` with `dir` as its XDG config directory.
+fn axtc(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_axtc"))
+        .args(args)
+        .current_dir(dir)
+        .env("XDG_CONFIG_HOME", dir)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn strict_checks_every_configured_app() {
+    let tmp = tempfile::tempdir().unwrap();
+    let themes = tmp.path().join("axtc/themes");
+    std::fs::create_dir_all(&themes).unwrap();
+    std::fs::copy(
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("themes/neon.toml"),
+        themes.join("neon.toml"),
+    )
+    .unwrap();
+    let warnings = |args: &[&str]| -> Vec<String> {
+        let out = axtc(tmp.path(), args);
+        assert!(out.status.success(), "{out:?}");
+        String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .filter(|l| l.starts_with("warning: "))
+            .map(str::to_owned)
+            .collect()
+    };
+
+    let all = warnings(&["apply", "neon", "--strict", "--dry-run"]);
+    let only = warnings(&[
+        "apply",
+        "neon",
+        "--strict",
+        "--dry-run",
+        "--only",
+        "alacritty",
+    ]);
+    assert_eq!(only, all);
+    assert!(!only.iter().any(|w| w.contains("'polybar.")), "{only:?}");
+}
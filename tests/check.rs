@@ -0,0 +1,65 @@
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// Run the axtc binary with `config` as its XDG config directory.
+fn axtc(config: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_axtc"))
+        .args(args)
+        .env("XDG_CONFIG_HOME", config)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn write_simulated_does_not_overwrite() {
+    let tmp = tempfile::tempdir().unwrap();
+    let themes = tmp.path().join("axtc/themes");
+    std::fs::create_dir_all(&themes).unwrap();
+    std::fs::copy(
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("themes/neon.toml"),
+        themes.join("neon.toml"),
+    )
+    .unwrap();
+    let args = [
+        "check",
+        "neon",
+        "--cvd",
+        "deuteranopia",
+        "--write-simulated",
+    ];
+    let simulated = themes.join("neon-deuteranopia.toml");
+
+    axtc(tmp.path(), &args);
+    assert!(simulated.is_file());
+
+    std::fs::write(&simulated, "name = \"neon-deuteranopia\" # hand-edited\n").unwrap();
+    let second = axtc(tmp.path(), &args);
+    assert_eq!(second.status.code(), Some(2));
+    assert!(
+        String::from_utf8_lossy(&second.stderr)
+            .contains("theme 'neon-deuteranopia' already exists")
+    );
+    assert!(
+        std::fs::read_to_string(&simulated)
+            .unwrap()
+            .contains("hand-edited")
+    );
+}
+
+#[test]
+fn errors_exit_differently_from_failures() {
+    let tmp = tempfile::tempdir().unwrap();
+    let themes = tmp.path().join("axtc/themes");
+    std::fs::create_dir_all(&themes).unwrap();
+    std::fs::write(
+        themes.join("low.toml"),
+        "name = \"low\"\n[ansi.primary]\nbackground = \"#000000\"\nforeground = \"#111111\"\n",
+    )
+    .unwrap();
+
+    let failing = axtc(tmp.path(), &["check", "low"]);
+    assert_eq!(failing.status.code(), Some(1));
+    let missing = axtc(tmp.path(), &["check", "missing"]);
+    assert_eq!(missing.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("theme 'missing' not found"));
+}
@@ -0,0 +1,35 @@
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// Run the axtc binary with `config` as its XDG config directory.
+fn axtc(config: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_axtc"))
+        .args(args)
+        .env("XDG_CONFIG_HOME", config)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn exit_codes_follow_diff() {
+    let tmp = tempfile::tempdir().unwrap();
+    let themes = tmp.path().join("axtc/themes");
+    std::fs::create_dir_all(&themes).unwrap();
+    std::fs::copy(
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("themes/neon.toml"),
+        themes.join("neon.toml"),
+    )
+    .unwrap();
+
+    let changed = axtc(tmp.path(), &["diff", "neon"]);
+    assert_eq!(changed.status.code(), Some(1));
+
+    let applied = axtc(tmp.path(), &["apply", "neon", "--no-reload"]);
+    assert!(applied.status.success(), "{applied:?}");
+    let unchanged = axtc(tmp.path(), &["diff", "neon"]);
+    assert_eq!(unchanged.status.code(), Some(0));
+
+    let missing = axtc(tmp.path(), &["diff", "missing"]);
+    assert_eq!(missing.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("theme 'missing' not found"));
+}
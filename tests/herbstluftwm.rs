@@ -19,6 +19,10 @@ fn minimal_theme() -> Theme {
         polybar: None,
         alacritty: None,
         picom: None,
+        roles: Default::default(),
+        variant: None,
+        variants: Default::default(),
+        extra: Default::default(),
     }
 }
 
@@ -28,6 +32,7 @@ fn minimal_hlwm() -> HerbstluftwmConfig {
         transparency: None,
         background_src: None,
         terminal: None,
+        extra: Default::default(),
     }
 }
 
@@ -89,6 +94,7 @@ fn global_font_used_in_dmenu() {
     let mut theme = minimal_theme();
     theme.global = Some(GlobalConfig {
         font: Some("JetBrainsMono".into()),
+        extra: Default::default(),
     });
     let out = render(&template_path(), &theme).unwrap();
     assert!(out.contains("JetBrainsMono:fontformat=truetype:style=Semibold:size=16"));
@@ -99,6 +105,7 @@ fn global_font_used_in_title_font() {
     let mut theme = minimal_theme();
     theme.global = Some(GlobalConfig {
         font: Some("JetBrainsMono".into()),
+        extra: Default::default(),
     });
     let out = render(&template_path(), &theme).unwrap();
     assert!(out.contains("JetBrainsMono:pixelsize=13:style=semibold"));
@@ -117,12 +124,14 @@ fn ansi_background_in_xsetroot() {
     let mut theme = minimal_theme();
     theme.ansi = Some(AnsiConfig {
         primary: Some(PrimaryColors {
-            background: Some("#1a1b26".into()),
+            background: Some("#1a1b26".parse().unwrap()),
             foreground: None,
             cursor: None,
+            extra: Default::default(),
         }),
         normal: None,
         bright: None,
+        extra: Default::default(),
     });
     let out = render(&template_path(), &theme).unwrap();
     assert!(out.contains("xsetroot -solid '#1a1b26'"));
@@ -139,14 +148,68 @@ fn ansi_magenta_in_frame_active_color() {
             green: None,
             yellow: None,
             blue: None,
-            magenta: Some("#bd93f9".into()),
+            magenta: Some("#bd93f9".parse().unwrap()),
             cyan: None,
             white: None,
+            extra: Default::default(),
         }),
         bright: None,
+        extra: Default::default(),
     });
     let out = render(&template_path(), &theme).unwrap();
-    assert!(out.contains("#bd93f9"));
+    assert!(out.contains("frame_bg_active_color     '#bd93f9aa'"));
+    assert!(out.contains("theme.active.color '#bd93f9ef'"));
+}
+
+#[test]
+fn accent_role_overrides_magenta() {
+    let mut theme = minimal_theme();
+    theme.roles = [("accent".to_owned(), "#00ffcc".parse().unwrap())].into();
+    let out = render(&template_path(), &theme).unwrap();
+    assert!(out.contains("frame_bg_active_color     '#00ffccaa'"));
+    assert!(out.contains("theme.active.inner_color       '#00ffcc'"));
+    assert!(out.contains("theme.urgent.color '#7811a1dd'"));
+}
+
+// ── Derived shades ────────────────────────────────────────────────────────────
+
+#[test]
+fn default_alpha_suffixes_preserved() {
+    let out = render(&template_path(), &minimal_theme()).unwrap();
+    assert!(out.contains("frame_bg_normal_color     '#565656aa'"));
+    assert!(out.contains("frame_bg_active_color     '#c11c84aa'"));
+    assert!(out.contains("theme.urgent.color '#7811a1dd'"));
+}
+
+#[test]
+fn frame_borders_derive_from_background() {
+    let mut theme = minimal_theme();
+    theme.ansi = Some(AnsiConfig {
+        primary: Some(PrimaryColors {
+            background: Some("#000000".parse().unwrap()),
+            foreground: Some("#ffffff".parse().unwrap()),
+            cursor: None,
+            extra: Default::default(),
+        }),
+        normal: None,
+        bright: None,
+        extra: Default::default(),
+    });
+    let out = render(&template_path(), &theme).unwrap();
+    assert!(out.contains("frame_border_active_color '#141414cc'"));
+    assert!(out.contains("frame_border_normal_color '#000000cc'"));
+    assert!(out.contains("theme.active.tab_title_color   '#cccccc'"));
+}
+
+#[test]
+fn shades_keep_literals_without_ansi() {
+    let out = render(&template_path(), &minimal_theme()).unwrap();
+    assert!(out.contains("frame_border_active_color '#222222cc'"));
+    assert!(out.contains("frame_border_normal_color '#101010cc'"));
+    assert!(out.contains("theme.tab_color                '#1f1f1fdd'"));
+    assert!(out.contains("theme.active.tab_outer_color   '#111111dd'"));
+    assert!(out.contains("theme.active.tab_title_color   '#ababab'"));
+    assert!(out.contains("theme.normal.title_color       '#898989'"));
 }
 
 // ── Startup applications ──────────────────────────────────────────────────────
@@ -17,6 +17,10 @@ fn minimal_theme() -> Theme {
         polybar: None,
         alacritty: None,
         picom: None,
+        roles: Default::default(),
+        variant: None,
+        variants: Default::default(),
+        extra: Default::default(),
     }
 }
 
@@ -25,6 +29,7 @@ fn picom(transparency: bool, blur: bool, corner_radius: u32) -> PicomConfig {
         transparency: Some(transparency),
         blur: Some(blur),
         corner_radius: Some(corner_radius),
+        extra: Default::default(),
     }
 }
 
@@ -27,6 +27,10 @@ fn minimal_theme() -> Theme {
         polybar: None,
         alacritty: None,
         picom: None,
+        roles: Default::default(),
+        variant: None,
+        variants: Default::default(),
+        extra: Default::default(),
     }
 }
 
@@ -52,12 +56,14 @@ fn ansi_primary_used_for_background_and_foreground() {
     let mut theme = minimal_theme();
     theme.ansi = Some(AnsiConfig {
         primary: Some(PrimaryColors {
-            background: Some("#11061c".into()),
-            foreground: Some("#dddddd".into()),
+            background: Some("#11061c".parse().unwrap()),
+            foreground: Some("#dddddd".parse().unwrap()),
             cursor: None,
+            extra: Default::default(),
         }),
         normal: None,
         bright: None,
+        extra: Default::default(),
     });
     let out = render(&template_path(), &theme).unwrap();
     assert!(out.contains("background     = #11061c"));
@@ -80,8 +86,9 @@ fn custom_primary_color() {
         position: None,
         height: None,
         font: None,
-        primary_color: Some("#aabbcc".into()),
+        primary_color: Some("#aabbcc".parse().unwrap()),
         background_alt: None,
+        extra: Default::default(),
     });
     let out = render(&template_path(), &theme).unwrap();
     assert!(out.contains("primary        = #aabbcc"));
@@ -95,7 +102,8 @@ fn custom_background_alt() {
         height: None,
         font: None,
         primary_color: None,
-        background_alt: Some("#deadbe".into()),
+        background_alt: Some("#deadbe".parse().unwrap()),
+        extra: Default::default(),
     });
     let out = render(&template_path(), &theme).unwrap();
     assert!(out.contains("background-alt = #deadbe"));
@@ -113,10 +121,12 @@ fn ansi_normal_cyan_used_for_secondary() {
             yellow: None,
             blue: None,
             magenta: None,
-            cyan: Some("#12abcd".into()),
+            cyan: Some("#12abcd".parse().unwrap()),
             white: None,
+            extra: Default::default(),
         }),
         bright: None,
+        extra: Default::default(),
     });
     let out = render(&template_path(), &theme).unwrap();
     assert!(out.contains("secondary      = #12abcd"));
@@ -129,7 +139,7 @@ fn ansi_bright_black_used_for_disabled() {
         primary: None,
         normal: None,
         bright: Some(BrightColors {
-            black: Some("#abcdef".into()),
+            black: Some("#abcdef".parse().unwrap()),
             red: None,
             green: None,
             yellow: None,
@@ -137,7 +147,9 @@ fn ansi_bright_black_used_for_disabled() {
             magenta: None,
             cyan: None,
             white: None,
+            extra: Default::default(),
         }),
+        extra: Default::default(),
     });
     let out = render(&template_path(), &theme).unwrap();
     assert!(out.contains("disabled       = #abcdef"));
@@ -150,6 +162,7 @@ fn app_font_takes_priority_over_global() {
     let mut theme = minimal_theme();
     theme.global = Some(GlobalConfig {
         font: Some("GlobalFont".into()),
+        extra: Default::default(),
     });
     theme.polybar = Some(PolybarConfig {
         position: None,
@@ -157,6 +170,7 @@ fn app_font_takes_priority_over_global() {
         font: Some("app-font:size=10".into()),
         primary_color: None,
         background_alt: None,
+        extra: Default::default(),
     });
     let out = render(&template_path(), &theme).unwrap();
     assert!(out.contains("font-0 = app-font:size=10"));
@@ -168,6 +182,7 @@ fn falls_back_to_global_font() {
     let mut theme = minimal_theme();
     theme.global = Some(GlobalConfig {
         font: Some("MyFont".into()),
+        extra: Default::default(),
     });
     let out = render(&template_path(), &theme).unwrap();
     assert!(out.contains("font-0 = MyFont:fontformat=truetype:style=Semibold:size=12;2"));
@@ -238,13 +253,15 @@ fn tags_py_uses_ansi_normal_yellow_for_occupied() {
             black: None,
             red: None,
             green: None,
-            yellow: Some("#aabbcc".into()),
+            yellow: Some("#aabbcc".parse().unwrap()),
             blue: None,
             magenta: None,
             cyan: None,
             white: None,
+            extra: Default::default(),
         }),
         bright: None,
+        extra: Default::default(),
     });
     let out = render(&tags_template_path(), &theme).unwrap();
     assert!(out.contains("OCCUPIED:         \"#aabbcc\""));
@@ -261,12 +278,14 @@ fn tags_py_uses_ansi_bright_yellow_for_focused() {
             black: None,
             red: None,
             green: None,
-            yellow: Some("#ddeeff".into()),
+            yellow: Some("#ddeeff".parse().unwrap()),
             blue: None,
             magenta: None,
             cyan: None,
             white: None,
+            extra: Default::default(),
         }),
+        extra: Default::default(),
     });
     let out = render(&tags_template_path(), &theme).unwrap();
     assert!(out.contains("FOCUSED_ACTIVE:   \"#ddeeff\""));
@@ -0,0 +1,120 @@
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use axtc::apply::reload::{ReloadOutcome, Reloader};
+use axtc::apply::{App, AppSpec};
+
+/// Write an executable stub that logs its name and arguments, then exits with `code`.
+fn stub(dir: &Path, name: &str, code: i32) {
+    let path = dir.join(name);
+    let log = dir.join("calls.log");
+    let script = format!(
+        "#!/bin/sh\necho \"{name} $*\" >> '{}'\nexit {code}\n",
+        log.display()
+    );
+    std::fs::write(&path, script).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+fn calls(dir: &Path) -> Vec<String> {
+    std::fs::read_to_string(dir.join("calls.log"))
+        .unwrap_or_default()
+        .lines()
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Serializes tests: writing a stub while another thread forks can make exec
+/// fail with "text file busy".
+static LOCK: Mutex<()> = Mutex::new(());
+
+fn stub_dir() -> ((MutexGuard<'static, ()>, tempfile::TempDir), PathBuf) {
+    let lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().to_path_buf();
+    ((lock, dir), path)
+}
+
+// ── herbstluftwm ──────────────────────────────────────────────────────────────
+
+#[test]
+fn herbstluftwm_reloads_via_herbstclient() {
+    let (_guard, dir) = stub_dir();
+    stub(&dir, "herbstclient", 0);
+    let outcome = Reloader::with_path(&dir).reload(&App::Herbstluftwm.into());
+    assert!(matches!(outcome, ReloadOutcome::Reloaded(_)));
+    assert_eq!(calls(&dir), ["herbstclient reload"]);
+}
+
+// ── polybar ───────────────────────────────────────────────────────────────────
+
+#[test]
+fn polybar_prefers_ipc_restart() {
+    let (_guard, dir) = stub_dir();
+    stub(&dir, "polybar-msg", 0);
+    stub(&dir, "python3", 0);
+    Reloader::with_path(&dir).reload(&App::Polybar.into());
+    assert_eq!(calls(&dir), ["polybar-msg cmd restart"]);
+}
+
+#[test]
+fn polybar_relaunches_when_ipc_fails() {
+    let (_guard, dir) = stub_dir();
+    stub(&dir, "polybar-msg", 1);
+    stub(&dir, "python3", 0);
+    let outcome = Reloader::with_path(&dir).reload(&App::Polybar.into());
+    let ReloadOutcome::Reloaded(step) = outcome else {
+        panic!("expected polybar to be relaunched");
+    };
+    assert_eq!(step.program, "python3");
+    let log = calls(&dir);
+    assert_eq!(log[0], "polybar-msg cmd restart");
+    assert!(log[1].starts_with("python3 ") && log[1].ends_with("polybar/launch.py"));
+}
+
+// ── picom ─────────────────────────────────────────────────────────────────────
+
+#[test]
+fn picom_is_signalled() {
+    let (_guard, dir) = stub_dir();
+    stub(&dir, "pkill", 0);
+    Reloader::with_path(&dir).reload(&App::Picom.into());
+    assert_eq!(calls(&dir), ["pkill -USR1 -x picom"]);
+}
+
+// ── alacritty ─────────────────────────────────────────────────────────────────
+
+#[test]
+fn alacritty_needs_no_reload() {
+    let (_guard, dir) = stub_dir();
+    let outcome = Reloader::with_path(&dir).reload(&App::Alacritty.into());
+    assert!(matches!(outcome, ReloadOutcome::NotNeeded));
+    assert!(calls(&dir).is_empty());
+}
+
+// ── Failures ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn failures_are_reported_without_aborting() {
+    let (_guard, dir) = stub_dir();
+    stub(&dir, "herbstclient", 3);
+    stub(&dir, "pkill", 0);
+    let apps: Vec<AppSpec> = [App::Herbstluftwm, App::Polybar, App::Picom]
+        .into_iter()
+        .map(AppSpec::from)
+        .collect();
+    let failures = Reloader::with_path(&dir).reload_all(&apps);
+    assert_eq!(failures, 2);
+    assert!(calls(&dir).contains(&"pkill -USR1 -x picom".to_owned()));
+}
+
+#[test]
+fn missing_executable_is_a_failure() {
+    let (_guard, dir) = stub_dir();
+    let outcome = Reloader::with_path(&dir).reload(&App::Herbstluftwm.into());
+    let ReloadOutcome::Failed(e) = outcome else {
+        panic!("expected failure");
+    };
+    assert!(e.to_string().contains("herbstclient reload"));
+}